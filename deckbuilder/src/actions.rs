@@ -0,0 +1,60 @@
+use crate::phase::TurnPhase;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Everything a player (human, frontend or bot) can do to a `CoreGameState`.
+/// All of them go through `CoreGameState::apply`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameAction {
+    /// Play the card at this index in the hand.
+    PlayCard(usize),
+    /// Finish the player's turn and let the enemy act.
+    EndTurn,
+    /// Add a user comment to the game log.
+    Comment(String),
+    /// Give up the fight.
+    Concede,
+}
+
+/// Why `CoreGameState::apply` rejected an action. Nothing is changed when an
+/// action is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionError {
+    InvalidCardIndex { index: usize, hand_size: usize },
+    NotEnoughMana { required: u32, available: u32 },
+    WrongPhase { phase: TurnPhase },
+    GameOver,
+    NothingToUndo,
+    NothingToRedo,
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::InvalidCardIndex { index, hand_size } => write!(
+                f,
+                "Invalid card index {} (you have {} cards in hand)",
+                index, hand_size
+            ),
+            ActionError::NotEnoughMana {
+                required,
+                available,
+            } => write!(
+                f,
+                "Not enough mana to play this card (costs {}, you have {})",
+                required, available
+            ),
+            ActionError::WrongPhase { phase } => {
+                write!(f, "That can't be done during the {:?} phase", phase)
+            }
+            ActionError::GameOver => write!(f, "The game is already over"),
+            ActionError::NothingToUndo => write!(
+                f,
+                "Nothing to undo (only actions since the start of the turn or the last card draw can be undone)"
+            ),
+            ActionError::NothingToRedo => write!(f, "Nothing to redo"),
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
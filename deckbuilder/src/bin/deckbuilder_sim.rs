@@ -0,0 +1,328 @@
+//! Plays many games with an automated player and reports balance statistics.
+//!
+//! Usage: deckbuilder_sim [--games N] [--seed S] [--policy NAME] [--enemy NAME]
+//!                        [--cards PATH] [--enemies PATH] [--max-turns N]
+
+use deckbuilder::{
+    actions::GameAction,
+    cards::CardDatabase,
+    enemies::{EnemyDatabase, EnemyDefinition},
+    events::{Combatant, GameEvent, GameOutcome, TurnEvent},
+    logging::{self, LogLevels},
+    policy::{policy_by_name, PlayerPolicy, POLICY_NAMES},
+    CoreGameState, Player, DEFAULT_ENEMY,
+};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Width of each bucket in the damage-taken histogram.
+const DAMAGE_BUCKET: u32 = 5;
+
+struct SimOptions {
+    games: u64,
+    seed: u64,
+    policy: String,
+    enemy: Option<String>,
+    cards: Option<PathBuf>,
+    enemies: Option<PathBuf>,
+    max_turns: u32,
+}
+
+impl Default for SimOptions {
+    fn default() -> Self {
+        SimOptions {
+            games: 1000,
+            seed: 0,
+            policy: POLICY_NAMES[0].to_string(),
+            enemy: None,
+            cards: None,
+            enemies: None,
+            max_turns: 100,
+        }
+    }
+}
+
+fn parse_args() -> SimOptions {
+    let mut options = SimOptions::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next();
+        match (arg.as_str(), value) {
+            ("--games", Some(games)) => options.games = parse_number(&arg, &games),
+            ("--seed", Some(seed)) => options.seed = parse_number(&arg, &seed),
+            ("--max-turns", Some(turns)) => options.max_turns = parse_number(&arg, &turns),
+            ("--policy", Some(policy)) => options.policy = policy,
+            ("--enemy", Some(enemy)) => options.enemy = Some(enemy),
+            ("--cards", Some(path)) => options.cards = Some(PathBuf::from(path)),
+            ("--enemies", Some(path)) => options.enemies = Some(PathBuf::from(path)),
+            (_, None) => fail(&format!("{} needs a value", arg)),
+            _ => fail(&format!("Unknown argument: {}", arg)),
+        }
+    }
+    options
+}
+
+fn parse_number<T: std::str::FromStr>(arg: &str, value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("{} expects a number, got \"{}\"", arg, value)))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+/// What happened in one simulated game.
+struct GameSummary {
+    /// `None` if the game hit the turn limit.
+    outcome: Option<GameOutcome>,
+    turns: u32,
+    damage_taken: u32,
+    damage_dealt: u32,
+    cards_played: Vec<String>,
+    turn_events: Vec<TurnEvent>,
+    /// Turn events rolled at the start of the turn the game ended on.
+    final_turn_events: Vec<TurnEvent>,
+}
+
+fn simulate(
+    seed: u64,
+    deck: Vec<deckbuilder::Card>,
+    enemy: &EnemyDefinition,
+    policy: &mut dyn PlayerPolicy,
+    max_turns: u32,
+) -> GameSummary {
+    let mut game = CoreGameState::with_enemy(seed, Player::with_deck(deck), enemy);
+    game.set_undo_enabled(false);
+    while game.outcome().is_none() && game.get_turn_count() <= max_turns {
+        let action = policy.choose_action(&game);
+        if game.apply(action).is_err() {
+            // A policy that picks an illegal play forfeits the rest of its turn
+            game.apply(GameAction::EndTurn)
+                .expect("Ending the turn is always allowed mid-game");
+        }
+    }
+    summarize(&game)
+}
+
+fn summarize(game: &CoreGameState) -> GameSummary {
+    let mut summary = GameSummary {
+        outcome: game.outcome(),
+        turns: game.get_turn_count(),
+        damage_taken: 0,
+        damage_dealt: 0,
+        cards_played: Vec::new(),
+        turn_events: Vec::new(),
+        final_turn_events: Vec::new(),
+    };
+    for entry in game.get_log() {
+        match &entry.event {
+            GameEvent::TurnStarted { .. } => summary.final_turn_events.clear(),
+            GameEvent::TurnEventTriggered(turn_event) => {
+                summary.turn_events.push(*turn_event);
+                summary.final_turn_events.push(*turn_event);
+            }
+            GameEvent::CardPlayed { card } => summary.cards_played.push(card.name.clone()),
+            GameEvent::DamageDealt { target, amount, .. }
+            | GameEvent::PoisonTicked { target, amount, .. } => match target {
+                Combatant::Player => summary.damage_taken += amount,
+                Combatant::Enemy => summary.damage_dealt += amount,
+            },
+            // The battlefield hurting the enemy isn't the player's doing
+            GameEvent::EnvironmentDamage {
+                target: Combatant::Player,
+                amount,
+                ..
+            } => summary.damage_taken += amount,
+            _ => {}
+        }
+    }
+    summary
+}
+
+#[derive(Default)]
+struct TurnEventStats {
+    games: u64,
+    wins: u64,
+    on_final_turn: u64,
+}
+
+fn percent(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * count as f64 / total as f64
+    }
+}
+
+fn average(total: u64, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total as f64 / count as f64
+    }
+}
+
+fn report(summaries: &[GameSummary]) {
+    let games = summaries.len() as u64;
+    let count = |outcome: Option<GameOutcome>| {
+        summaries
+            .iter()
+            .filter(|summary| summary.outcome == outcome)
+            .count() as u64
+    };
+    let wins = count(Some(GameOutcome::Victory));
+    let losses = count(Some(GameOutcome::Defeat));
+    let unfinished = count(None);
+    println!(
+        "Win rate: {:.1}%  (victories {}, defeats {}, hit the turn limit {})",
+        percent(wins, games),
+        wins,
+        losses,
+        unfinished
+    );
+
+    let turns_when = |outcome: GameOutcome| {
+        let matching = summaries
+            .iter()
+            .filter(|summary| summary.outcome == Some(outcome));
+        let (total, count) = matching.fold((0, 0), |(total, count), summary| {
+            (total + u64::from(summary.turns), count + 1)
+        });
+        average(total, count)
+    };
+    let total_turns = summaries.iter().map(|s| u64::from(s.turns)).sum::<u64>();
+    println!(
+        "Average turns: {:.2}  (victories {:.2}, defeats {:.2})",
+        average(total_turns, games),
+        turns_when(GameOutcome::Victory),
+        turns_when(GameOutcome::Defeat)
+    );
+    let total_dealt = summaries
+        .iter()
+        .map(|s| u64::from(s.damage_dealt))
+        .sum::<u64>();
+    println!(
+        "Average damage dealt per turn: {:.2}",
+        average(total_dealt, total_turns)
+    );
+
+    let mut damage_buckets = BTreeMap::<u32, u64>::new();
+    for summary in summaries {
+        *damage_buckets
+            .entry(summary.damage_taken / DAMAGE_BUCKET)
+            .or_default() += 1;
+    }
+    println!("\nDamage taken per game:");
+    let widest = damage_buckets.values().copied().max().unwrap_or(1);
+    for (bucket, games_in_bucket) in &damage_buckets {
+        let low = bucket * DAMAGE_BUCKET;
+        println!(
+            "  {:>3}-{:<3} {:>6}  {}",
+            low,
+            low + DAMAGE_BUCKET - 1,
+            games_in_bucket,
+            "#".repeat((games_in_bucket * 40).div_ceil(widest) as usize)
+        );
+    }
+
+    let mut card_plays = BTreeMap::<&str, u64>::new();
+    for name in summaries.iter().flat_map(|summary| &summary.cards_played) {
+        *card_plays.entry(name).or_default() += 1;
+    }
+    let total_plays = card_plays.values().sum::<u64>();
+    let mut card_plays = card_plays.into_iter().collect::<Vec<_>>();
+    card_plays.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    println!(
+        "\nCard plays ({:.2} per game):",
+        average(total_plays, games)
+    );
+    for (name, plays) in card_plays {
+        println!(
+            "  {:<20} {:>8}  {:>5.1}%",
+            name,
+            plays,
+            percent(plays, total_plays)
+        );
+    }
+
+    let mut turn_events = BTreeMap::<String, TurnEventStats>::new();
+    for summary in summaries {
+        let mut seen = summary.turn_events.clone();
+        seen.sort_by_key(|turn_event| format!("{:?}", turn_event));
+        seen.dedup();
+        for turn_event in seen {
+            let stats = turn_events.entry(format!("{:?}", turn_event)).or_default();
+            stats.games += 1;
+            if summary.outcome == Some(GameOutcome::Victory) {
+                stats.wins += 1;
+            }
+            if summary.final_turn_events.contains(&turn_event) {
+                stats.on_final_turn += 1;
+            }
+        }
+    }
+    println!("\nTurn events (games it happened in, win rate in those games, games it happened on the deciding turn):");
+    for (name, stats) in turn_events {
+        println!(
+            "  {:<18} {:>6}  {:>5.1}%  {:>6}",
+            name,
+            stats.games,
+            percent(stats.wins, stats.games),
+            stats.on_final_turn
+        );
+    }
+}
+
+fn main() {
+    let options = parse_args();
+    let cards = match &options.cards {
+        Some(path) => CardDatabase::load(path)
+            .unwrap_or_else(|err| fail(&format!("Failed to load cards: {}", err))),
+        None => CardDatabase::builtin(),
+    };
+    let enemies = match &options.enemies {
+        Some(path) => EnemyDatabase::load(path)
+            .unwrap_or_else(|err| fail(&format!("Failed to load enemies: {}", err))),
+        None => EnemyDatabase::builtin(),
+    };
+    let enemy_name = options.enemy.as_deref().unwrap_or(DEFAULT_ENEMY);
+    let enemy = enemies
+        .get(enemy_name)
+        .unwrap_or_else(|| fail(&format!("Unknown enemy \"{}\"", enemy_name)));
+    let mut policy = policy_by_name(&options.policy, options.seed).unwrap_or_else(|| {
+        fail(&format!(
+            "Unknown policy \"{}\". Available policies: {}",
+            options.policy,
+            POLICY_NAMES.join(", ")
+        ))
+    });
+    let deck = cards.starter_deck();
+    if deck.is_empty() {
+        fail("The card data has no starter deck");
+    }
+
+    logging::configure_from_env(LogLevels::default());
+    let summaries = (0..options.games)
+        .map(|game| {
+            simulate(
+                options.seed.wrapping_add(game),
+                deck.clone(),
+                enemy,
+                policy.as_mut(),
+                options.max_turns,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    println!(
+        "Simulated {} games against {} with the {} policy (seeds {} to {})\n",
+        options.games,
+        enemy.name,
+        policy.name(),
+        options.seed,
+        options.seed.wrapping_add(options.games.saturating_sub(1))
+    );
+    report(&summaries);
+}
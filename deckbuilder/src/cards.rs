@@ -0,0 +1,206 @@
+use crate::data::{self, DataLoadError};
+use crate::{Card, SpecialAbility};
+use serde::Deserialize;
+use std::path::Path;
+
+/// The card file compiled into the binary, used when no card data is given.
+const BUILTIN_CARDS: &str = include_str!("../../Godottest/res/data/cards/mountain.ron");
+const BUILTIN_CARDS_FILE: &str = "res://res/data/cards/mountain.ron";
+
+/// One card as written in a card data file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CardDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub attack: u32,
+    #[serde(default)]
+    pub defense: u32,
+    pub mana_cost: u32,
+    #[serde(default)]
+    pub lucky: bool,
+    #[serde(default)]
+    pub special_ability: Option<SpecialAbility>,
+    /// How many copies a new player's deck starts with.
+    #[serde(default)]
+    pub starter_copies: u32,
+}
+
+impl CardDefinition {
+    pub fn to_card(&self) -> Card {
+        Card {
+            name: self.name.clone(),
+            attack: self.attack,
+            defense: self.defense,
+            lucky: self.lucky,
+            special_ability: self.special_ability.clone(),
+            mana_cost: self.mana_cost,
+        }
+    }
+}
+
+/// All card definitions known to the game, gathered from one or more
+/// RON card files.
+#[derive(Debug, Clone, Default)]
+pub struct CardDatabase {
+    cards: Vec<CardDefinition>,
+}
+
+impl CardDatabase {
+    /// The cards shipped with the game.
+    pub fn builtin() -> Self {
+        let mut database = Self::default();
+        database
+            .add_source(BUILTIN_CARDS, BUILTIN_CARDS_FILE)
+            .expect("Built-in card data is valid");
+        database
+    }
+
+    /// Loads a single card file, or every `.ron` file in a directory.
+    pub fn load(path: &Path) -> Result<Self, DataLoadError> {
+        let mut database = Self::default();
+        for file in data::ron_files(path)? {
+            let source = data::read_file(&file)?;
+            database.add_source(&source, &file.display().to_string())?;
+        }
+        Ok(database)
+    }
+
+    /// Parses and validates the contents of a card file. `file` is only used
+    /// in error messages, so frontends that read files themselves (e.g. from
+    /// Godot's `res://`) can pass the path they read from.
+    pub fn add_source(&mut self, source: &str, file: &str) -> Result<(), DataLoadError> {
+        let definitions: Vec<CardDefinition> = data::parse_ron(source, file)?;
+
+        let first_new = self.cards.len();
+        for definition in definitions {
+            // Which definition of this name in the file we're at, so a
+            // duplicate is reported at its own line rather than the first one
+            let occurrence = self.cards[first_new..]
+                .iter()
+                .filter(|card| card.name == definition.name)
+                .count();
+            let error = |message: String| DataLoadError {
+                file: file.to_string(),
+                line: data::line_of_name(source, &definition.name, occurrence),
+                message,
+            };
+            if definition.name.trim().is_empty() {
+                return Err(error("card name must not be empty".to_string()));
+            }
+            if self.get(&definition.name).is_some() {
+                return Err(error(format!(
+                    "card \"{}\" is defined more than once",
+                    definition.name
+                )));
+            }
+            if definition.attack == 0
+                && definition.defense == 0
+                && definition.special_ability.is_none()
+            {
+                return Err(error(format!(
+                    "card \"{}\" has no attack, defense or special ability",
+                    definition.name
+                )));
+            }
+            self.cards.push(definition);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CardDefinition> {
+        self.cards.iter().find(|card| card.name == name)
+    }
+
+    pub fn cards(&self) -> &[CardDefinition] {
+        &self.cards
+    }
+
+    /// A new player's deck with `starter_copies` of each card. Copies are
+    /// dealt round-robin in file order, so the deck interleaves the cards
+    /// rather than stacking every copy of one card together.
+    pub fn starter_deck(&self) -> Vec<Card> {
+        let rounds = self
+            .cards
+            .iter()
+            .map(|definition| definition.starter_copies)
+            .max()
+            .unwrap_or(0);
+        (0..rounds)
+            .flat_map(|round| {
+                self.cards
+                    .iter()
+                    .filter(move |definition| definition.starter_copies > round)
+                    .map(CardDefinition::to_card)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "test_cards.ron";
+
+    fn load_error(source: &str) -> String {
+        CardDatabase::default()
+            .add_source(source, FILE)
+            .expect_err("The card file is invalid")
+            .to_string()
+    }
+
+    #[test]
+    fn unknown_ability_names_file_and_line() {
+        let error = load_error(
+            r#"[
+    (name: "Strike", attack: 3, mana_cost: 1),
+    (name: "Blink", mana_cost: 1, special_ability: Some(Teleport(2))),
+]"#,
+        );
+        assert!(error.starts_with("test_cards.ron:3:"), "{}", error);
+        assert!(error.contains("Teleport"), "{}", error);
+    }
+
+    #[test]
+    fn duplicate_card_names_file_and_line() {
+        let error = load_error(
+            r#"[
+    (name: "Strike", attack: 3, mana_cost: 1),
+    (name: "Guard", defense: 3, mana_cost: 1),
+    (name: "Strike", attack: 4, mana_cost: 2),
+]"#,
+        );
+        assert_eq!(
+            error,
+            "test_cards.ron:4: card \"Strike\" is defined more than once"
+        );
+    }
+
+    #[test]
+    fn negative_cost_names_file_and_line() {
+        let error = load_error(
+            r#"[
+    (name: "Strike", attack: 3, mana_cost: 1),
+    (
+        name: "Freebie",
+        attack: 1,
+        mana_cost: -1,
+    ),
+]"#,
+        );
+        assert!(error.starts_with("test_cards.ron:6:"), "{}", error);
+    }
+
+    #[test]
+    fn card_that_does_nothing_is_rejected() {
+        let error = load_error(
+            r#"[
+    (name: "Dud", mana_cost: 1),
+]"#,
+        );
+        assert_eq!(
+            error,
+            "test_cards.ron:2: card \"Dud\" has no attack, defense or special ability"
+        );
+    }
+}
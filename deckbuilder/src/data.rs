@@ -0,0 +1,72 @@
+//! Shared plumbing for the RON game data files (cards, enemies).
+
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A problem with a game data file. `line` is 1-based and present whenever
+/// the problem can be pinned to a place in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLoadError {
+    pub file: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for DataLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file, line, self.message),
+            None => write!(f, "{}: {}", self.file, self.message),
+        }
+    }
+}
+
+impl std::error::Error for DataLoadError {}
+
+/// The data files at `path`: the file itself, or every `.ron` file in it if
+/// it's a directory, sorted so load order doesn't depend on the platform.
+pub(crate) fn ron_files(path: &Path) -> Result<Vec<PathBuf>, DataLoadError> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = std::fs::read_dir(path)
+        .map_err(|err| io_error(path, err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.extension().is_some_and(|ext| ext == "ron"))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<String, DataLoadError> {
+    std::fs::read_to_string(path).map_err(|err| io_error(path, err))
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> DataLoadError {
+    DataLoadError {
+        file: path.display().to_string(),
+        line: None,
+        message: err.to_string(),
+    }
+}
+
+pub(crate) fn parse_ron<T: DeserializeOwned>(source: &str, file: &str) -> Result<T, DataLoadError> {
+    ron::from_str(source).map_err(|err| DataLoadError {
+        file: file.to_string(),
+        line: Some(err.position.line),
+        message: err.code.to_string(),
+    })
+}
+
+/// Finds the line declaring the `occurrence`th entry with this name, for
+/// error messages about an entry that parsed fine but failed validation.
+pub(crate) fn line_of_name(source: &str, name: &str, occurrence: usize) -> Option<usize> {
+    let quoted = format!("\"{}\"", name);
+    source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains("name") && line.contains(&quoted))
+        .nth(occurrence)
+        .map(|(index, _)| index + 1)
+}
@@ -0,0 +1,283 @@
+use crate::data::{self, DataLoadError};
+use crate::intents::EnemyBehavior;
+use crate::status::StatusEffect;
+use crate::Enemy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The enemy files compiled into the binary, used when no enemy data is given.
+const BUILTIN_ENEMIES: &[(&str, &str)] = &[
+    (
+        include_str!("../../Godottest/res/data/enemies/mountain_sentinel.ron"),
+        "res://res/data/enemies/mountain_sentinel.ron",
+    ),
+    (
+        include_str!("../../Godottest/res/data/enemies/mountain_foes.ron"),
+        "res://res/data/enemies/mountain_foes.ron",
+    ),
+];
+
+/// Something an enemy says, rendered as `Name verb: "line"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnemyLine {
+    pub verb: String,
+    pub line: String,
+}
+
+/// What an enemy says in response to specific things happening to it.
+/// Every line in a list is spoken, in order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnemyReactions {
+    pub heal: Vec<EnemyLine>,
+    pub draw_cards: Vec<EnemyLine>,
+    pub apply_poison: Vec<EnemyLine>,
+    pub stun_enemy: Vec<EnemyLine>,
+    pub summon_avalanche: Vec<EnemyLine>,
+    /// When the enemy takes poison damage at the start of its turn.
+    pub poison_tick: Vec<EnemyLine>,
+    /// When the enemy loses its attack to a stun.
+    pub stunned: Vec<EnemyLine>,
+}
+
+/// One enemy as written in an enemy data file. `{name}` in any text is
+/// replaced with the enemy's name.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyDefinition {
+    pub name: String,
+    pub health: u32,
+    pub attack: u32,
+    /// Block the enemy starts the fight with. Like any block, it's gone by
+    /// the enemy's first turn.
+    #[serde(default)]
+    pub shield: u32,
+    /// Boss enemies end a run instead of appearing as regular fights.
+    #[serde(default)]
+    pub boss: bool,
+    #[serde(default = "default_rage_threshold")]
+    pub rage_threshold: u32,
+    #[serde(default = "default_rage_attack_bonus")]
+    pub rage_attack_bonus: u32,
+    #[serde(default)]
+    pub taunts: Vec<String>,
+    /// Narration before the enemy's stats are shown.
+    #[serde(default)]
+    pub intro: Vec<String>,
+    /// Narration leading into the enemy's opening taunt.
+    #[serde(default)]
+    pub declaration: Option<String>,
+    /// Narration after the opening taunt, before the first hand is drawn.
+    #[serde(default)]
+    pub battle_start: Vec<String>,
+    #[serde(default)]
+    pub defeat: Option<EnemyLine>,
+    #[serde(default)]
+    pub reactions: EnemyReactions,
+    /// Which intents the enemy picks from turn to turn. Plain attacks if
+    /// left out.
+    #[serde(default)]
+    pub behavior: EnemyBehavior,
+}
+
+fn default_rage_threshold() -> u32 {
+    100
+}
+
+fn default_rage_attack_bonus() -> u32 {
+    1
+}
+
+impl EnemyDefinition {
+    pub fn to_enemy(&self) -> Enemy {
+        let mut enemy = Enemy::new(
+            self.name.clone(),
+            self.health,
+            self.attack,
+            self.taunts
+                .iter()
+                .map(|taunt| self.fill_name(taunt))
+                .collect(),
+        );
+        enemy.statuses.apply(StatusEffect::Block, self.shield);
+        enemy.rage_threshold = self.rage_threshold;
+        enemy.rage_attack_bonus = self.rage_attack_bonus;
+        enemy.defeat = self.defeat.clone();
+        enemy.reactions = self.reactions.clone();
+        enemy.behavior = self.behavior.clone();
+        enemy
+    }
+
+    /// Replaces `{name}` with this enemy's name.
+    pub fn fill_name(&self, text: &str) -> String {
+        text.replace("{name}", &self.name)
+    }
+}
+
+/// All enemy definitions known to the game, gathered from one or more RON
+/// enemy files.
+#[derive(Debug, Clone, Default)]
+pub struct EnemyDatabase {
+    enemies: Vec<EnemyDefinition>,
+}
+
+impl EnemyDatabase {
+    /// The enemies shipped with the game.
+    pub fn builtin() -> Self {
+        let mut database = Self::default();
+        for (source, file) in BUILTIN_ENEMIES {
+            database
+                .add_source(source, file)
+                .expect("Built-in enemy data is valid");
+        }
+        database
+    }
+
+    /// Loads a single enemy file, or every `.ron` file in a directory.
+    pub fn load(path: &Path) -> Result<Self, DataLoadError> {
+        let mut database = Self::default();
+        for file in data::ron_files(path)? {
+            let source = data::read_file(&file)?;
+            database.add_source(&source, &file.display().to_string())?;
+        }
+        Ok(database)
+    }
+
+    /// Parses and validates the contents of an enemy file. `file` is only
+    /// used in error messages.
+    pub fn add_source(&mut self, source: &str, file: &str) -> Result<(), DataLoadError> {
+        let definitions: Vec<EnemyDefinition> = data::parse_ron(source, file)?;
+
+        let first_new = self.enemies.len();
+        for definition in definitions {
+            let occurrence = self.enemies[first_new..]
+                .iter()
+                .filter(|enemy| enemy.name == definition.name)
+                .count();
+            let error = |message: String| DataLoadError {
+                file: file.to_string(),
+                line: data::line_of_name(source, &definition.name, occurrence),
+                message,
+            };
+            if definition.name.trim().is_empty() {
+                return Err(error("enemy name must not be empty".to_string()));
+            }
+            if self.get(&definition.name).is_some() {
+                return Err(error(format!(
+                    "enemy \"{}\" is defined more than once",
+                    definition.name
+                )));
+            }
+            if definition.health == 0 {
+                return Err(error(format!(
+                    "enemy \"{}\" must have more than 0 health",
+                    definition.name
+                )));
+            }
+            if definition.rage_threshold == 0 {
+                return Err(error(format!(
+                    "enemy \"{}\" must have a rage_threshold above 0",
+                    definition.name
+                )));
+            }
+            if definition.behavior.intents().is_empty() {
+                return Err(error(format!(
+                    "enemy \"{}\" has a behavior with no intents",
+                    definition.name
+                )));
+            }
+            self.enemies.push(definition);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EnemyDefinition> {
+        self.enemies.iter().find(|enemy| enemy.name == name)
+    }
+
+    pub fn enemies(&self) -> &[EnemyDefinition] {
+        &self.enemies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "test_enemies.ron";
+
+    fn load_error(source: &str) -> String {
+        EnemyDatabase::default()
+            .add_source(source, FILE)
+            .expect_err("The enemy file is invalid")
+            .to_string()
+    }
+
+    #[test]
+    fn unknown_intent_names_file_and_line() {
+        let error = load_error(
+            r#"[
+    (
+        name: "Yeti",
+        health: 20,
+        attack: 3,
+        behavior: Cycle([
+            Attack(bonus: 0),
+            Hibernate(turns: 2),
+        ]),
+    ),
+]"#,
+        );
+        assert!(error.starts_with("test_enemies.ron:8:"), "{}", error);
+        assert!(error.contains("Hibernate"), "{}", error);
+    }
+
+    #[test]
+    fn unknown_behavior_names_file_and_line() {
+        let error = load_error(
+            r#"[
+    (
+        name: "Yeti",
+        health: 20,
+        attack: 3,
+        behavior: Shuffle([Attack(bonus: 0)]),
+    ),
+]"#,
+        );
+        assert!(error.starts_with("test_enemies.ron:6:"), "{}", error);
+        assert!(error.contains("Shuffle"), "{}", error);
+    }
+
+    #[test]
+    fn behavior_without_intents_names_file_and_line() {
+        let error = load_error(
+            r#"[
+    (name: "Yeti", health: 20, attack: 3),
+    (name: "Avalanche Spirit", health: 10, attack: 2, behavior: Random([])),
+]"#,
+        );
+        assert_eq!(
+            error,
+            "test_enemies.ron:3: enemy \"Avalanche Spirit\" has a behavior with no intents"
+        );
+    }
+
+    #[test]
+    fn duplicate_enemy_names_file_and_line() {
+        let mut enemies = EnemyDatabase::default();
+        enemies
+            .add_source(r#"[(name: "Yeti", health: 20, attack: 3)]"#, "first.ron")
+            .unwrap();
+        let error = enemies
+            .add_source(
+                r#"[
+    (name: "Yeti", health: 30, attack: 4),
+]"#,
+                FILE,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "test_enemies.ron:2: enemy \"Yeti\" is defined more than once"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::intents::EnemyIntent;
+use crate::status::StatusEffect;
+use crate::Card;
+use serde::{Deserialize, Serialize};
+
+/// Which side of the fight an event applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Combatant {
+    Player,
+    Enemy,
+}
+
+/// Where a drawn card came from, so frontends can present draws differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DrawSource {
+    InitialHand,
+    TurnStart,
+    TurnEvent,
+    Ability,
+}
+
+/// The random battlefield events rolled by `CoreGameState::handle_turn_events`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnEvent {
+    GustOfWind,
+    GroundTremor,
+    MysteriousEnergy,
+    AncientPower,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameOutcome {
+    Victory,
+    Defeat,
+    Conceded,
+}
+
+/// Everything that happens in a game, in the order it happened.
+///
+/// Effects are reported as separate events (a card play is a `CardPlayed`
+/// followed by the `DamageDealt`/`Healed`/... events it caused), so consumers
+/// never have to parse text. `render::render_event` turns these into the
+/// console sentences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    Narration(String),
+    EnemyAppeared {
+        name: String,
+        health: u32,
+        attack: u32,
+    },
+    TurnStarted {
+        turn: u32,
+    },
+    TurnEventTriggered(TurnEvent),
+    CardDrawn {
+        card: Card,
+        source: DrawSource,
+    },
+    CardPlayed {
+        card: Card,
+    },
+    DamageDealt {
+        target: Combatant,
+        amount: u32,
+        health_before: u32,
+        health_after: u32,
+    },
+    /// Damage from the battlefield itself, like a ground tremor, rather than
+    /// from either side.
+    EnvironmentDamage {
+        target: Combatant,
+        amount: u32,
+        health_before: u32,
+        health_after: u32,
+    },
+    Healed {
+        target: Combatant,
+        amount: u32,
+        health_before: u32,
+        health_after: u32,
+    },
+    StatusApplied {
+        target: Combatant,
+        effect: StatusEffect,
+        stacks: u32,
+        total: u32,
+    },
+    DamageBlocked {
+        target: Combatant,
+        amount: u32,
+    },
+    HandDiscarded {
+        cards: usize,
+    },
+    MaxManaIncreased {
+        amount: u32,
+    },
+    AvalancheSummoned {
+        damage: u32,
+    },
+    PoisonTicked {
+        target: Combatant,
+        amount: u32,
+        health_after: u32,
+    },
+    Stunned {
+        turns: u32,
+    },
+    TurnSkipped {
+        enemy: String,
+    },
+    EnemyPowerSurge {
+        enemy: String,
+        bonus: u32,
+    },
+    IntentRevealed {
+        enemy: String,
+        intent: EnemyIntent,
+        /// Damage the intent will deal before block, 0 for non-attacks.
+        damage: u32,
+    },
+    EnemyBuffed {
+        enemy: String,
+        attack: u32,
+        total: u32,
+    },
+    ManaDrained {
+        enemy: String,
+        mana: u32,
+    },
+    MinionsSummoned {
+        enemy: String,
+        count: u32,
+        total: u32,
+    },
+    EnemyAttacked {
+        enemy: String,
+        damage: u32,
+    },
+    EnemyTaunt {
+        enemy: String,
+        verb: String,
+        line: String,
+    },
+    GameOver(GameOutcome),
+    UserComment(String),
+}
@@ -0,0 +1,100 @@
+//! `SynthPlayer`, an audio player that streams a `Synth` through a single
+//! long-lived `AudioStreamGenerator`.
+
+use crate::synth::{Sound, SoundPreset, Synth, SAMPLE_RATE};
+use godot::classes::{
+    AudioStreamGenerator, AudioStreamGeneratorPlayback, AudioStreamPlayer, IAudioStreamPlayer,
+};
+use godot::prelude::*;
+
+/// How much audio the generator buffers ahead, in seconds. Longer survives
+/// slow frames; shorter makes sounds start sooner.
+const BUFFER_SECONDS: f32 = 0.1;
+
+/// Plays synthesized sound effects. The generator keeps running and is
+/// topped up every frame, with silence between sounds, so starting a sound
+/// is just adding it to the mix.
+#[derive(GodotClass)]
+#[class(base=AudioStreamPlayer)]
+pub struct SynthPlayer {
+    #[base]
+    base: Base<AudioStreamPlayer>,
+    synth: Synth,
+    playback: Option<Gd<AudioStreamGeneratorPlayback>>,
+    /// Reused between frames to avoid allocating.
+    samples: Vec<f32>,
+}
+
+#[godot_api]
+impl IAudioStreamPlayer for SynthPlayer {
+    fn init(base: Base<AudioStreamPlayer>) -> Self {
+        Self {
+            base,
+            synth: Synth::new(SAMPLE_RATE),
+            playback: None,
+            samples: Vec::new(),
+        }
+    }
+
+    fn ready(&mut self) {
+        let mut generator = AudioStreamGenerator::new_gd();
+        generator.set_mix_rate(SAMPLE_RATE);
+        generator.set_buffer_length(BUFFER_SECONDS);
+        self.base_mut()
+            .set_stream(generator.upcast::<AudioStream>());
+        self.base_mut().play();
+        self.playback = self
+            .base_mut()
+            .get_stream_playback()
+            .and_then(|playback| playback.try_cast::<AudioStreamGeneratorPlayback>().ok());
+        if self.playback.is_none() {
+            godot_error!("SynthPlayer could not start its generator");
+        }
+    }
+
+    fn process(&mut self, _delta: f64) {
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        let frames = playback.get_frames_available().max(0) as usize;
+        if frames == 0 {
+            return;
+        }
+        self.samples.resize(frames, 0.0);
+        self.synth.fill(&mut self.samples);
+        let buffer = self
+            .samples
+            .iter()
+            .map(|&sample| Vector2::new(sample, sample))
+            .collect::<PackedVector2Array>();
+        playback.push_buffer(buffer);
+    }
+}
+
+#[godot_api]
+impl SynthPlayer {
+    /// Plays one of the named sounds: "card_play", "hit", "heal", "poison"
+    /// or "stun". Returns false for any other name.
+    #[func]
+    fn play_sound(&mut self, name: GString) -> bool {
+        let name = name.to_string();
+        let Some(sound) = Sound::from_name(&name) else {
+            godot_error!("Unknown sound \"{}\"", name);
+            return false;
+        };
+        self.play_preset(sound.preset());
+        true
+    }
+
+    /// Plays a short sine blip at `frequency` Hz.
+    #[func]
+    fn play_boop(&mut self, frequency: f32) {
+        self.play_preset(SoundPreset::boop(frequency));
+    }
+}
+
+impl SynthPlayer {
+    pub(crate) fn play_preset(&mut self, preset: SoundPreset) {
+        self.synth.play(preset);
+    }
+}
@@ -0,0 +1,413 @@
+//! `BattleScene`, a complete fight built from Rust: the enemy panel, the
+//! player's health and mana, the combat log, the hand and floating damage
+//! numbers, all following a `DeckbuilderGame` it creates for itself.
+
+use crate::godot_audio::SynthPlayer;
+use crate::godot_game::DeckbuilderGame;
+use crate::godot_hand::HandView;
+use crate::render::{render_intent, render_statuses};
+use crate::status::{StatusEffect, Statuses};
+use crate::synth::Sound;
+use crate::CoreGameState;
+use godot::classes::{
+    Button, Control, IControl, Label, PanelContainer, ProgressBar, RichTextLabel, VBoxContainer,
+};
+use godot::global::HorizontalAlignment;
+use godot::prelude::*;
+
+const ENEMY_PANEL: Rect2 = Rect2::new(Vector2::new(440.0, 20.0), Vector2::new(400.0, 180.0));
+const PLAYER_PANEL: Rect2 = Rect2::new(Vector2::new(20.0, 560.0), Vector2::new(260.0, 140.0));
+const LOG_PANEL: Rect2 = Rect2::new(Vector2::new(960.0, 20.0), Vector2::new(300.0, 520.0));
+const HAND_AREA: Rect2 = Rect2::new(Vector2::new(300.0, 520.0), Vector2::new(640.0, 240.0));
+const END_TURN_BUTTON: Rect2 = Rect2::new(Vector2::new(1080.0, 690.0), Vector2::new(180.0, 50.0));
+const RESULT_LABEL: Rect2 = Rect2::new(Vector2::new(440.0, 260.0), Vector2::new(400.0, 60.0));
+
+/// How far a damage or heal number rises, and how long it takes to fade.
+const FLOAT_DISTANCE: f32 = 60.0;
+const FLOAT_SECONDS: f64 = 0.8;
+const DAMAGE_COLOR: Color = Color::from_rgba(1.0, 0.3, 0.3, 1.0);
+const HEAL_COLOR: Color = Color::from_rgba(0.4, 1.0, 0.4, 1.0);
+
+/// The controls the scene keeps up to date.
+struct BattleWidgets {
+    enemy_panel: Gd<PanelContainer>,
+    enemy_name: Gd<Label>,
+    enemy_health: Gd<ProgressBar>,
+    enemy_health_text: Gd<Label>,
+    enemy_indicators: Gd<Label>,
+    enemy_intent: Gd<Label>,
+    player_panel: Gd<PanelContainer>,
+    player_health: Gd<ProgressBar>,
+    player_health_text: Gd<Label>,
+    player_mana: Gd<Label>,
+    player_statuses: Gd<Label>,
+    log: Gd<RichTextLabel>,
+    end_turn: Gd<Button>,
+    result: Gd<Label>,
+}
+
+/// A fight against `enemy` (the default enemy if empty), started as soon as
+/// the scene is ready. `seed` is the game's seed, or -1 for a random one.
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct BattleScene {
+    #[base]
+    base: Base<Control>,
+    #[export]
+    enemy: GString,
+    #[export]
+    seed: i64,
+    game: Option<Gd<DeckbuilderGame>>,
+    widgets: Option<BattleWidgets>,
+    sounds: Option<Gd<SynthPlayer>>,
+}
+
+#[godot_api]
+impl IControl for BattleScene {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            enemy: GString::new(),
+            seed: -1,
+            game: None,
+            widgets: None,
+            sounds: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let mut game = DeckbuilderGame::new_alloc();
+        self.base_mut().add_child(game.clone().upcast::<Node>());
+        for (signal, handler) in [
+            ("game_started", "on_game_started"),
+            ("state_changed", "on_state_changed"),
+            ("log_entry", "on_log_entry"),
+            ("damage_dealt", "on_damage_dealt"),
+            ("healed", "on_healed"),
+            ("game_over", "on_game_over"),
+            ("card_played", "on_card_played"),
+            ("status_applied", "on_status_applied"),
+            ("enemy_stunned", "on_enemy_stunned"),
+        ] {
+            game.connect(signal.into(), self.base().callable(handler));
+        }
+
+        let sounds = SynthPlayer::new_alloc();
+        self.base_mut().add_child(sounds.clone().upcast::<Node>());
+        self.sounds = Some(sounds);
+
+        let widgets = self.build_widgets();
+        let mut hand = HandView::new_alloc();
+        place(&mut hand, HAND_AREA);
+        self.base_mut().add_child(hand.clone().upcast::<Node>());
+        hand.bind_mut().follow(
+            game.clone(),
+            Some(widgets.enemy_panel.clone().upcast::<Control>()),
+        );
+
+        self.widgets = Some(widgets);
+        self.game = Some(game);
+        self.start_game();
+    }
+}
+
+#[godot_api]
+impl BattleScene {
+    #[func]
+    fn on_game_started(&mut self, _enemy: GString) {
+        if let Some(widgets) = &mut self.widgets {
+            widgets.result.set_visible(false);
+            widgets.end_turn.set_text("End turn".into());
+        }
+    }
+
+    #[func]
+    fn on_state_changed(&mut self) {
+        // The game is still mid-action when it signals, so redraw afterwards
+        self.base_mut().call_deferred("refresh".into(), &[]);
+    }
+
+    /// Redraws every panel from the game.
+    #[func]
+    fn refresh(&mut self) {
+        let Some(game) = self.game.clone() else {
+            return;
+        };
+        let game = game.bind();
+        let Some(core) = game.core() else {
+            return;
+        };
+        let Some(widgets) = &mut self.widgets else {
+            return;
+        };
+
+        let enemy = &core.enemy;
+        widgets.enemy_name.set_text(enemy.name.as_str().into());
+        show_health(
+            &mut widgets.enemy_health,
+            &mut widgets.enemy_health_text,
+            enemy.health,
+            enemy.max_health,
+        );
+        widgets
+            .enemy_indicators
+            .set_text(enemy_indicators(core).into());
+        let intent = core
+            .enemy_intent()
+            .map(|intent| {
+                format!(
+                    "Intends to {}",
+                    render_intent(intent, core.incoming_damage())
+                )
+            })
+            .unwrap_or_default();
+        widgets.enemy_intent.set_text(intent.into());
+
+        let player = &core.player;
+        show_health(
+            &mut widgets.player_health,
+            &mut widgets.player_health_text,
+            player.health,
+            player.max_health,
+        );
+        widgets
+            .player_mana
+            .set_text(format!("Mana: {}/{}", player.current_mana, player.max_mana).into());
+        widgets
+            .player_statuses
+            .set_text(format!("Statuses: {}", render_statuses(&player.statuses)).into());
+    }
+
+    #[func]
+    fn on_log_entry(&mut self, text: GString) {
+        if let Some(widgets) = &mut self.widgets {
+            widgets.log.append_text(format!("{}\n", text).into());
+        }
+    }
+
+    #[func]
+    fn on_damage_dealt(&mut self, target: GString, amount: i64, _health_after: i64) {
+        if amount > 0 {
+            self.play_sound(Sound::Hit);
+            self.float_number(&target, format!("-{}", amount), DAMAGE_COLOR);
+        }
+    }
+
+    #[func]
+    fn on_healed(&mut self, target: GString, amount: i64, _health_after: i64) {
+        if amount > 0 {
+            self.play_sound(Sound::Heal);
+            self.float_number(&target, format!("+{}", amount), HEAL_COLOR);
+        }
+    }
+
+    #[func]
+    fn on_card_played(&mut self, _card: GString) {
+        self.play_sound(Sound::CardPlay);
+    }
+
+    #[func]
+    fn on_status_applied(&mut self, _target: GString, effect: GString, _total: i64) {
+        if StatusEffect::from_name(&effect.to_string()) == Some(StatusEffect::Poison) {
+            self.play_sound(Sound::Poison);
+        }
+    }
+
+    #[func]
+    fn on_enemy_stunned(&mut self, _turns: i64) {
+        self.play_sound(Sound::Stun);
+    }
+
+    #[func]
+    fn on_game_over(&mut self, outcome: GString) {
+        if let Some(widgets) = &mut self.widgets {
+            widgets.result.set_text(outcome);
+            widgets.result.set_visible(true);
+            widgets.end_turn.set_text("New game".into());
+        }
+    }
+
+    #[func]
+    fn on_end_turn_pressed(&mut self) {
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        if game
+            .bind()
+            .core()
+            .is_some_and(|core| core.outcome().is_some())
+        {
+            self.start_game();
+        } else {
+            game.call_deferred("end_turn".into(), &[]);
+        }
+    }
+}
+
+impl BattleScene {
+    fn start_game(&mut self) {
+        let args = [self.enemy.to_variant(), self.seed.to_variant()];
+        if let Some(game) = &mut self.game {
+            // Deferred, as the game signals back into this scene while it
+            // sets up
+            game.call_deferred("start_game".into(), &args);
+        }
+    }
+
+    fn play_sound(&mut self, sound: Sound) {
+        if let Some(sounds) = &mut self.sounds {
+            sounds.bind_mut().play_preset(sound.preset());
+        }
+    }
+
+    fn build_widgets(&mut self) -> BattleWidgets {
+        let mut enemy_name = Label::new_alloc();
+        enemy_name.set_horizontal_alignment(HorizontalAlignment::CENTER);
+        let enemy_health = ProgressBar::new_alloc();
+        let enemy_health_text = Label::new_alloc();
+        let enemy_indicators = Label::new_alloc();
+        let enemy_intent = Label::new_alloc();
+        let enemy_panel = self.add_panel(
+            ENEMY_PANEL,
+            &[
+                enemy_name.clone().upcast(),
+                enemy_health.clone().upcast(),
+                enemy_health_text.clone().upcast(),
+                enemy_indicators.clone().upcast(),
+                enemy_intent.clone().upcast(),
+            ],
+        );
+
+        let player_health = ProgressBar::new_alloc();
+        let player_health_text = Label::new_alloc();
+        let player_mana = Label::new_alloc();
+        let player_statuses = Label::new_alloc();
+        let mut player_title = Label::new_alloc();
+        player_title.set_text("You".into());
+        let player_panel = self.add_panel(
+            PLAYER_PANEL,
+            &[
+                player_title.upcast(),
+                player_health.clone().upcast(),
+                player_health_text.clone().upcast(),
+                player_mana.clone().upcast(),
+                player_statuses.clone().upcast(),
+            ],
+        );
+
+        let mut log = RichTextLabel::new_alloc();
+        log.set_scroll_follow(true);
+        place(&mut log, LOG_PANEL);
+        self.base_mut().add_child(log.clone().upcast::<Node>());
+
+        let mut end_turn = Button::new_alloc();
+        end_turn.set_text("End turn".into());
+        place(&mut end_turn, END_TURN_BUTTON);
+        end_turn.connect(
+            "pressed".into(),
+            self.base().callable("on_end_turn_pressed"),
+        );
+        self.base_mut().add_child(end_turn.clone().upcast::<Node>());
+
+        let mut result = Label::new_alloc();
+        result.set_horizontal_alignment(HorizontalAlignment::CENTER);
+        result.set_visible(false);
+        place(&mut result, RESULT_LABEL);
+        self.base_mut().add_child(result.clone().upcast::<Node>());
+
+        BattleWidgets {
+            enemy_panel,
+            enemy_name,
+            enemy_health,
+            enemy_health_text,
+            enemy_indicators,
+            enemy_intent,
+            player_panel,
+            player_health,
+            player_health_text,
+            player_mana,
+            player_statuses,
+            log,
+            end_turn,
+            result,
+        }
+    }
+
+    /// Adds a panel at `rect` with `rows` stacked inside it.
+    fn add_panel(&mut self, rect: Rect2, rows: &[Gd<Control>]) -> Gd<PanelContainer> {
+        let mut column = VBoxContainer::new_alloc();
+        for row in rows {
+            column.add_child(row.clone().upcast::<Node>());
+        }
+        let mut panel = PanelContainer::new_alloc();
+        panel.add_child(column.upcast::<Node>());
+        place(&mut panel, rect);
+        self.base_mut().add_child(panel.clone().upcast::<Node>());
+        panel
+    }
+
+    /// Shows `text` rising and fading out of the target's panel.
+    fn float_number(&mut self, target: &GString, text: String, color: Color) {
+        let Some(widgets) = &self.widgets else {
+            return;
+        };
+        let panel = if target.to_string() == "player" {
+            &widgets.player_panel
+        } else {
+            &widgets.enemy_panel
+        };
+        let start = panel.get_position() + panel.get_size() / 2.0;
+
+        let mut number = Label::new_alloc();
+        number.set_text(text.into());
+        number.set_modulate(color);
+        number.set_position(start);
+        number.set_z_index(20);
+        self.base_mut().add_child(number.clone().upcast::<Node>());
+
+        let Some(mut tween) = number.create_tween() else {
+            return;
+        };
+        tween.tween_property(
+            number.clone().upcast::<Object>(),
+            "position".into(),
+            Variant::from(start - Vector2::new(0.0, FLOAT_DISTANCE)),
+            FLOAT_SECONDS,
+        );
+        tween.parallel();
+        tween.tween_property(
+            number.clone().upcast::<Object>(),
+            "modulate:a".into(),
+            Variant::from(0.0),
+            FLOAT_SECONDS,
+        );
+        tween.tween_callback(Callable::from_object_method(&number, "queue_free"));
+    }
+}
+
+fn place<T: Inherits<Control>>(control: &mut Gd<T>, rect: Rect2) {
+    let mut control = control.clone().upcast::<Control>();
+    control.set_position(rect.position);
+    control.set_size(rect.size);
+}
+
+fn show_health(bar: &mut Gd<ProgressBar>, text: &mut Gd<Label>, health: u32, max_health: u32) {
+    bar.set_max(f64::from(max_health.max(1)));
+    bar.set_value(f64::from(health));
+    text.set_text(format!("Health: {}/{}", health, max_health).into());
+}
+
+/// The enemy's statuses, stun and rage, e.g.
+/// "Block 3, Poison 2  Stunned 1 turn(s)  Rage 4/10".
+fn enemy_indicators(core: &CoreGameState) -> String {
+    let enemy = &core.enemy;
+    let mut parts = Vec::new();
+    if enemy.statuses != Statuses::default() {
+        parts.push(render_statuses(&enemy.statuses));
+    }
+    if enemy.stunned > 0 {
+        parts.push(format!("Stunned {} turn(s)", enemy.stunned));
+    }
+    parts.push(format!("Rage {}/{}", enemy.rage, enemy.rage_threshold));
+    parts.join("  ")
+}
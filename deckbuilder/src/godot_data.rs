@@ -0,0 +1,93 @@
+use crate::cards::CardDatabase;
+use crate::data::DataLoadError;
+use crate::enemies::EnemyDatabase;
+use crate::logging::log_at;
+use godot::classes::{DirAccess, FileAccess};
+use godot::global::Error;
+use godot::prelude::godot_error;
+
+/// Where the game's card files live inside the Godot project.
+pub const CARDS_DIR: &str = "res://res/data/cards";
+/// Where the game's enemy files live inside the Godot project.
+pub const ENEMIES_DIR: &str = "res://res/data/enemies";
+
+/// Loads the card and enemy data from the Godot project, falling back to the
+/// built-in data for whichever fails to load.
+pub fn load_game_data() -> (CardDatabase, EnemyDatabase) {
+    let cards = match load_card_database(CARDS_DIR) {
+        Ok(cards) => {
+            log_at!(
+                Info,
+                Game,
+                "Loaded {} card definitions",
+                cards.cards().len()
+            );
+            cards
+        }
+        Err(err) => {
+            godot_error!("Failed to load cards, using built-in set: {}", err);
+            CardDatabase::builtin()
+        }
+    };
+    let enemies = match load_enemy_database(ENEMIES_DIR) {
+        Ok(enemies) => {
+            log_at!(
+                Info,
+                Game,
+                "Loaded {} enemy definitions",
+                enemies.enemies().len()
+            );
+            enemies
+        }
+        Err(err) => {
+            godot_error!("Failed to load enemies, using built-in set: {}", err);
+            EnemyDatabase::builtin()
+        }
+    };
+    (cards, enemies)
+}
+
+pub fn load_card_database(dir: &str) -> Result<CardDatabase, DataLoadError> {
+    let mut database = CardDatabase::default();
+    for (path, source) in read_ron_files(dir)? {
+        database.add_source(&source, &path)?;
+    }
+    Ok(database)
+}
+
+pub fn load_enemy_database(dir: &str) -> Result<EnemyDatabase, DataLoadError> {
+    let mut database = EnemyDatabase::default();
+    for (path, source) in read_ron_files(dir)? {
+        database.add_source(&source, &path)?;
+    }
+    Ok(database)
+}
+
+/// Reads every `.ron` file in a `res://` directory through Godot's file API,
+/// so it works in the editor and from an exported pack alike. Returns
+/// `(path, contents)` pairs sorted by path.
+fn read_ron_files(dir: &str) -> Result<Vec<(String, String)>, DataLoadError> {
+    let mut files = DirAccess::get_files_at(dir.into())
+        .to_vec()
+        .iter()
+        .map(|file| file.to_string())
+        .filter(|file| file.ends_with(".ron"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+        .into_iter()
+        .map(|file| {
+            let path = format!("{}/{}", dir, file);
+            let source = FileAccess::get_file_as_string(path.clone().into()).to_string();
+            let open_error = FileAccess::get_open_error();
+            if open_error != Error::OK {
+                return Err(DataLoadError {
+                    file: path,
+                    line: None,
+                    message: format!("could not be read ({:?})", open_error),
+                });
+            }
+            Ok((path, source))
+        })
+        .collect()
+}
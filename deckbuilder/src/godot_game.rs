@@ -0,0 +1,524 @@
+//! `DeckbuilderGame`, a node that runs a `CoreGameState` for Godot scenes.
+//! Scenes read the game through its funcs, play through `play_card` and
+//! `end_turn`, and follow what happens through its signals.
+
+use crate::actions::GameAction;
+use crate::cards::CardDatabase;
+use crate::enemies::EnemyDatabase;
+use crate::events::{Combatant, GameEvent};
+use crate::log_export::LogFormat;
+use crate::logger::{LogCursor, LogFilter};
+use crate::logging::log_at;
+use crate::render::{render_ability, render_event, render_intent, render_status};
+use crate::status::Statuses;
+use crate::{godot_data, godot_save};
+use crate::{Card, CoreGameState, Player, DEFAULT_ENEMY};
+use godot::prelude::*;
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct DeckbuilderGame {
+    #[base]
+    base: Base<Node>,
+    cards: CardDatabase,
+    enemies: EnemyDatabase,
+    game: Option<CoreGameState>,
+    /// How far through the game's log signals have been sent.
+    log_cursor: LogCursor,
+}
+
+#[godot_api]
+impl INode for DeckbuilderGame {
+    fn init(base: Base<Node>) -> Self {
+        let (cards, enemies) = godot_data::load_game_data();
+        Self {
+            base,
+            cards,
+            enemies,
+            game: None,
+            log_cursor: LogCursor::default(),
+        }
+    }
+}
+
+#[godot_api]
+impl DeckbuilderGame {
+    /// Emitted once a new game is set up and waiting on the player.
+    #[signal]
+    fn game_started(enemy: GString);
+
+    /// Emitted for every new log entry, with its console text. Redoing an
+    /// action sends its entries again.
+    #[signal]
+    fn log_entry(text: GString);
+
+    #[signal]
+    fn turn_started(turn: i64);
+
+    #[signal]
+    fn card_drawn(card: GString);
+
+    #[signal]
+    fn card_played(card: GString);
+
+    /// `target` is "player" or "enemy". Poison damage is reported here too.
+    #[signal]
+    fn damage_dealt(target: GString, amount: i64, health_after: i64);
+
+    #[signal]
+    fn healed(target: GString, amount: i64, health_after: i64);
+
+    #[signal]
+    fn damage_blocked(target: GString, amount: i64);
+
+    /// `effect` is the effect's id (`StatusEffect::name`, e.g. "poison"),
+    /// and `total` the target's stacks of it after it was applied.
+    #[signal]
+    fn status_applied(target: GString, effect: GString, total: i64);
+
+    #[signal]
+    fn enemy_stunned(turns: i64);
+
+    /// `description` reads like "attack for 3 damage".
+    #[signal]
+    fn intent_revealed(description: GString);
+
+    /// `outcome` is "Victory", "Defeat" or "Conceded".
+    #[signal]
+    fn game_over(outcome: GString);
+
+    /// Emitted after every action and when a game starts, once all other
+    /// signals are out, so a scene can redraw everything in one place.
+    #[signal]
+    fn state_changed();
+
+    /// Names of every card definition the game knows about.
+    #[func]
+    fn card_names(&self) -> PackedStringArray {
+        self.cards
+            .cards()
+            .iter()
+            .map(|card| GString::from(card.name.as_str()))
+            .collect()
+    }
+
+    /// Names of every enemy that a game can be started against.
+    #[func]
+    fn enemy_names(&self) -> PackedStringArray {
+        self.enemies
+            .enemies()
+            .iter()
+            .map(|enemy| GString::from(enemy.name.as_str()))
+            .collect()
+    }
+
+    /// Starts a game against the named enemy, or the default enemy if the
+    /// name is empty. A negative seed picks a random one.
+    #[func]
+    fn start_game(&mut self, enemy_name: GString, seed: i64) -> bool {
+        let enemy_name = enemy_name.to_string();
+        let enemy_name = if enemy_name.is_empty() {
+            DEFAULT_ENEMY
+        } else {
+            enemy_name.as_str()
+        };
+        let Some(enemy) = self.enemies.get(enemy_name) else {
+            godot_error!("Unknown enemy \"{}\"", enemy_name);
+            return false;
+        };
+        let seed = u64::try_from(seed).unwrap_or_else(|_| rand::random());
+        let player = Player::with_deck(self.cards.starter_deck());
+        self.game = Some(CoreGameState::with_enemy(seed, player, enemy));
+        self.log_cursor = LogCursor::default();
+        self.emit_new_entries();
+        self.emit("game_started", &[GString::from(enemy_name).to_variant()]);
+        self.emit("state_changed", &[]);
+        true
+    }
+
+    /// Saves the current game to `path`, or to `user://savegame.ron` if the
+    /// path is empty.
+    #[func]
+    fn save_game(&self, path: GString) -> bool {
+        let Some(game) = &self.game else {
+            godot_error!("There is no game to save");
+            return false;
+        };
+        let path = save_path(&path);
+        match godot_save::save_game(&path, game) {
+            Ok(()) => {
+                log_at!(Info, Game, "Game saved to {}", path);
+                true
+            }
+            Err(err) => {
+                godot_error!("Failed to save the game: {}", err);
+                false
+            }
+        }
+    }
+
+    /// Replaces the current game with the one saved at `path` (or
+    /// `user://savegame.ron` if the path is empty). Signals `game_started`
+    /// as a new game would, but not the loaded game's old log entries.
+    #[func]
+    fn load_game(&mut self, path: GString) -> bool {
+        let path = save_path(&path);
+        match godot_save::load_game(&path) {
+            Ok(game) => {
+                log_at!(Info, Game, "Game loaded from {}", path);
+                let enemy = GString::from(game.enemy.name.as_str());
+                self.log_cursor = game.log_cursor();
+                self.game = Some(game);
+                self.emit("game_started", &[enemy.to_variant()]);
+                self.emit("state_changed", &[]);
+                true
+            }
+            Err(err) => {
+                godot_error!("Failed to load the game: {}", err);
+                false
+            }
+        }
+    }
+
+    /// Whether a game has been started. A finished game still counts until
+    /// the next one starts.
+    #[func]
+    fn has_game(&self) -> bool {
+        self.game.is_some()
+    }
+
+    #[func]
+    fn is_over(&self) -> bool {
+        self.game
+            .as_ref()
+            .is_some_and(|game| game.outcome().is_some())
+    }
+
+    #[func]
+    fn turn(&self) -> i64 {
+        self.game
+            .as_ref()
+            .map_or(0, |game| i64::from(game.get_turn_count()))
+    }
+
+    /// The cards in hand, in order, as dictionaries with `name`, `attack`,
+    /// `defense`, `mana_cost`, `ability` (empty if none) and `affordable`.
+    #[func]
+    fn get_hand(&self) -> Array<Dictionary> {
+        let Some(game) = &self.game else {
+            return Array::new();
+        };
+        game.get_hand()
+            .iter()
+            .map(|card| card_dictionary(game, card))
+            .collect()
+    }
+
+    /// The player's `health`, `max_health`, `mana`, `max_mana`, `deck_size`,
+    /// `discard_size` and `statuses` (status id, as sent by `status_applied`,
+    /// to its display `text` and `stacks`).
+    #[func]
+    fn get_player(&self) -> Dictionary {
+        let Some(game) = &self.game else {
+            return Dictionary::new();
+        };
+        let player = &game.player;
+        dict! {
+            "health": player.health,
+            "max_health": player.max_health,
+            "mana": player.current_mana,
+            "max_mana": player.max_mana,
+            "deck_size": player.deck.len() as i64,
+            "discard_size": player.discard_pile.len() as i64,
+            "statuses": statuses_dictionary(&player.statuses),
+        }
+    }
+
+    /// The enemy's `name`, `health`, `max_health`, `attack`, `stunned`
+    /// (turns), `rage`, `rage_threshold`, `minions`, `statuses` (as in
+    /// `get_player`), `intent` (empty if none) and `incoming_damage`.
+    #[func]
+    fn get_enemy(&self) -> Dictionary {
+        let Some(game) = &self.game else {
+            return Dictionary::new();
+        };
+        let enemy = &game.enemy;
+        let intent = game
+            .enemy_intent()
+            .map(|intent| render_intent(intent, game.incoming_damage()))
+            .unwrap_or_default();
+        dict! {
+            "name": GString::from(enemy.name.as_str()),
+            "health": enemy.health,
+            "max_health": enemy.max_health,
+            "attack": enemy.attack,
+            "stunned": enemy.stunned,
+            "rage": enemy.rage,
+            "rage_threshold": enemy.rage_threshold,
+            "minions": enemy.minions,
+            "statuses": statuses_dictionary(&enemy.statuses),
+            "intent": GString::from(intent),
+            "incoming_damage": game.incoming_damage(),
+        }
+    }
+
+    /// The whole log as console text, one entry per line.
+    #[func]
+    fn get_log(&self) -> PackedStringArray {
+        self.game
+            .as_ref()
+            .map(|game| game.render_log().into_iter().map(GString::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// The whole log as "text", "jsonl" or "markdown" (a match report), for
+    /// saving or sharing. Empty if there is no game or the format is unknown.
+    #[func]
+    fn export_log(&self, format: GString) -> GString {
+        let Some(game) = &self.game else {
+            return GString::new();
+        };
+        let Some(format) = LogFormat::from_name(&format.to_string()) else {
+            godot_error!("Unknown log format \"{}\"", format);
+            return GString::new();
+        };
+        GString::from(game.export_log(format, &LogFilter::default()))
+    }
+
+    /// Plays the card at `index` in the hand. Returns false, changing
+    /// nothing, if it can't be played.
+    #[func]
+    fn play_card(&mut self, index: i64) -> bool {
+        let Ok(index) = usize::try_from(index) else {
+            godot_error!("Invalid card index {}", index);
+            return false;
+        };
+        self.apply(GameAction::PlayCard(index))
+    }
+
+    /// Ends the player's turn; the enemy's turn and the start of the next one
+    /// play out before this returns.
+    #[func]
+    fn end_turn(&mut self) -> bool {
+        self.apply(GameAction::EndTurn)
+    }
+
+    #[func]
+    fn concede(&mut self) -> bool {
+        self.apply(GameAction::Concede)
+    }
+
+    #[func]
+    fn undo(&mut self) -> bool {
+        let undone = self.game.as_mut().is_some_and(|game| game.undo().is_ok());
+        if undone {
+            // Moves the cursor back to the end of the shortened log
+            self.emit_new_entries();
+            self.emit("state_changed", &[]);
+        }
+        undone
+    }
+
+    #[func]
+    fn redo(&mut self) -> bool {
+        let redone = self.game.as_mut().is_some_and(|game| game.redo().is_ok());
+        if redone {
+            self.emit_new_entries();
+            self.emit("state_changed", &[]);
+        }
+        redone
+    }
+
+    /// The suggested next play, for a hint button: `card` is the index in
+    /// the hand to play, or -1 to end the turn, and `reason` says why. Empty
+    /// when the game isn't waiting on the player.
+    #[func]
+    fn suggest_play(&self) -> Dictionary {
+        let mut hint = Dictionary::new();
+        if let Some(suggestion) = self.game.as_ref().and_then(CoreGameState::suggest_play) {
+            let card = suggestion.card.map_or(-1, |index| index as i64);
+            hint.set(GString::from("card"), card);
+            hint.set(
+                GString::from("reason"),
+                GString::from(suggestion.reason.to_string()),
+            );
+        }
+        hint
+    }
+}
+
+impl DeckbuilderGame {
+    /// The running game, for other Rust nodes to draw from.
+    pub(crate) fn core(&self) -> Option<&CoreGameState> {
+        self.game.as_ref()
+    }
+
+    fn apply(&mut self, action: GameAction) -> bool {
+        let Some(game) = &mut self.game else {
+            godot_error!("No game has been started");
+            return false;
+        };
+        match game.apply(action) {
+            Ok(_) => {
+                self.emit_new_entries();
+                self.emit("state_changed", &[]);
+                true
+            }
+            Err(err) => {
+                godot_error!("{}", err);
+                false
+            }
+        }
+    }
+
+    /// Sends the signals for every log entry added since the last call.
+    fn emit_new_entries(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let events = game
+            .log_since(&mut self.log_cursor)
+            .iter()
+            .map(|entry| entry.event.clone())
+            .collect::<Vec<_>>();
+        self.emit_events(&events);
+    }
+
+    fn emit_events(&mut self, events: &[GameEvent]) {
+        for event in events {
+            let text = render_event(event);
+            self.emit("log_entry", &[GString::from(text).to_variant()]);
+            match event {
+                GameEvent::TurnStarted { turn } => {
+                    self.emit("turn_started", &[turn.to_variant()]);
+                }
+                GameEvent::CardDrawn { card, .. } => {
+                    self.emit("card_drawn", &[card_name(card)]);
+                }
+                GameEvent::CardPlayed { card } => {
+                    self.emit("card_played", &[card_name(card)]);
+                }
+                GameEvent::DamageDealt {
+                    target,
+                    amount,
+                    health_after,
+                    ..
+                }
+                | GameEvent::EnvironmentDamage {
+                    target,
+                    amount,
+                    health_after,
+                    ..
+                }
+                | GameEvent::PoisonTicked {
+                    target,
+                    amount,
+                    health_after,
+                } => self.emit(
+                    "damage_dealt",
+                    &[
+                        combatant(*target),
+                        amount.to_variant(),
+                        health_after.to_variant(),
+                    ],
+                ),
+                GameEvent::Healed {
+                    target,
+                    amount,
+                    health_after,
+                    ..
+                } => self.emit(
+                    "healed",
+                    &[
+                        combatant(*target),
+                        amount.to_variant(),
+                        health_after.to_variant(),
+                    ],
+                ),
+                GameEvent::DamageBlocked { target, amount } => {
+                    self.emit("damage_blocked", &[combatant(*target), amount.to_variant()])
+                }
+                GameEvent::StatusApplied {
+                    target,
+                    effect,
+                    total,
+                    ..
+                } => self.emit(
+                    "status_applied",
+                    &[
+                        combatant(*target),
+                        GString::from(effect.name()).to_variant(),
+                        total.to_variant(),
+                    ],
+                ),
+                GameEvent::IntentRevealed { intent, damage, .. } => self.emit(
+                    "intent_revealed",
+                    &[GString::from(render_intent(*intent, *damage)).to_variant()],
+                ),
+                GameEvent::Stunned { turns } => {
+                    self.emit("enemy_stunned", &[turns.to_variant()]);
+                }
+                GameEvent::GameOver(outcome) => self.emit(
+                    "game_over",
+                    &[GString::from(format!("{:?}", outcome)).to_variant()],
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    fn emit(&mut self, signal: &str, args: &[Variant]) {
+        self.base_mut().emit_signal(signal.into(), args);
+    }
+}
+
+fn card_name(card: &Card) -> Variant {
+    GString::from(card.name.as_str()).to_variant()
+}
+
+fn combatant(target: Combatant) -> Variant {
+    let name = match target {
+        Combatant::Player => "player",
+        Combatant::Enemy => "enemy",
+    };
+    GString::from(name).to_variant()
+}
+
+fn card_dictionary(game: &CoreGameState, card: &Card) -> Dictionary {
+    let ability = card
+        .special_ability
+        .as_ref()
+        .map(render_ability)
+        .unwrap_or_default();
+    dict! {
+        "name": GString::from(card.name.as_str()),
+        "attack": card.attack,
+        "defense": card.defense,
+        "mana_cost": card.mana_cost,
+        "ability": GString::from(ability),
+        "affordable": game.can_afford(card),
+    }
+}
+
+/// Active statuses keyed by the same ids the `status_applied` signal sends
+/// (e.g. "poison"), each with its display `text` and `stacks`.
+fn statuses_dictionary(statuses: &Statuses) -> Dictionary {
+    statuses
+        .active()
+        .map(|(effect, stacks)| {
+            let status = dict! {
+                "text": GString::from(render_status(effect)),
+                "stacks": stacks,
+            };
+            (GString::from(effect.name()), status)
+        })
+        .collect()
+}
+
+fn save_path(path: &GString) -> String {
+    if path.is_empty() {
+        godot_save::SAVE_FILE.to_string()
+    } else {
+        path.to_string()
+    }
+}
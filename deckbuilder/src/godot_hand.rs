@@ -0,0 +1,348 @@
+//! The player's hand as Godot controls: `HandView` lays out one `CardView`
+//! per card in a fan and plays cards on the `DeckbuilderGame` it follows.
+//! Cards are played by clicking them or by dragging them onto the enemy.
+
+use crate::godot_game::DeckbuilderGame;
+use crate::render::render_ability;
+use crate::Card;
+use godot::classes::control::MouseFilter;
+use godot::classes::text_server::AutowrapMode;
+use godot::classes::{
+    Control, IControl, IPanelContainer, InputEvent, InputEventMouseButton, InputEventMouseMotion,
+    Label, PanelContainer, VBoxContainer,
+};
+use godot::global::{HorizontalAlignment, MouseButton};
+use godot::prelude::*;
+
+const CARD_SIZE: Vector2 = Vector2::new(140.0, 200.0);
+/// Horizontal distance between neighbouring cards in the fan.
+const CARD_SPACING: f32 = 110.0;
+/// How far each card is turned from its neighbour, in radians.
+const FAN_ANGLE: f32 = 0.06;
+/// How far each step from the middle of the fan drops a card, squared.
+const FAN_DROP: f32 = 6.0;
+const HOVER_SCALE: f32 = 1.25;
+const HOVER_SECONDS: f64 = 0.1;
+/// A press and release closer together than this count as a click.
+const CLICK_DISTANCE: f32 = 8.0;
+const RAISED_Z_INDEX: i32 = 10;
+const UNAFFORDABLE_TINT: Color = Color::from_rgba(0.45, 0.45, 0.45, 1.0);
+
+/// One card in the hand. It reports clicks and drops to its `HandView`,
+/// which decides whether they play the card.
+#[derive(GodotClass)]
+#[class(base=PanelContainer)]
+pub struct CardView {
+    #[base]
+    base: Base<PanelContainer>,
+    index: usize,
+    affordable: bool,
+    /// Where the fan put the card, to return to after hovering or dragging.
+    rest_position: Vector2,
+    rest_rotation: f32,
+    /// Global mouse position at the press, and its offset from the card,
+    /// while the card is held.
+    drag: Option<(Vector2, Vector2)>,
+}
+
+#[godot_api]
+impl IPanelContainer for CardView {
+    fn init(base: Base<PanelContainer>) -> Self {
+        Self {
+            base,
+            index: 0,
+            affordable: true,
+            rest_position: Vector2::ZERO,
+            rest_rotation: 0.0,
+            drag: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let entered = self.base().callable("on_mouse_entered");
+        let exited = self.base().callable("on_mouse_exited");
+        self.base_mut().connect("mouse_entered".into(), entered);
+        self.base_mut().connect("mouse_exited".into(), exited);
+    }
+
+    fn gui_input(&mut self, event: Gd<InputEvent>) {
+        if !self.affordable {
+            return;
+        }
+        let event = match event.try_cast::<InputEventMouseButton>() {
+            Ok(button) => {
+                if button.get_button_index() == MouseButton::LEFT {
+                    self.on_left_button(button.is_pressed(), button.get_global_position());
+                    self.base_mut().accept_event();
+                }
+                return;
+            }
+            Err(event) => event,
+        };
+        if let (Ok(motion), Some((_, grab_offset))) =
+            (event.try_cast::<InputEventMouseMotion>(), self.drag)
+        {
+            self.base_mut()
+                .set_global_position(motion.get_global_position() - grab_offset);
+            self.base_mut().accept_event();
+        }
+    }
+}
+
+#[godot_api]
+impl CardView {
+    /// The card was clicked without being dragged.
+    #[signal]
+    fn clicked(index: i64);
+
+    /// The card was dragged and let go with the mouse at `position`, in
+    /// global coordinates.
+    #[signal]
+    fn dropped(index: i64, position: Vector2);
+
+    #[func]
+    fn on_mouse_entered(&mut self) {
+        if self.drag.is_none() {
+            self.base_mut().set_z_index(RAISED_Z_INDEX);
+            self.animate_scale(HOVER_SCALE);
+        }
+    }
+
+    #[func]
+    fn on_mouse_exited(&mut self) {
+        if self.drag.is_none() {
+            self.base_mut().set_z_index(0);
+            self.animate_scale(1.0);
+        }
+    }
+}
+
+impl CardView {
+    /// Fills in the card's labels. `index` is its place in the hand.
+    pub(crate) fn show_card(&mut self, index: usize, card: &Card, affordable: bool) {
+        self.index = index;
+        self.affordable = affordable;
+
+        let mut column = VBoxContainer::new_alloc();
+        column.set_mouse_filter(MouseFilter::IGNORE);
+        column.add_child(label(&card.name).upcast::<Node>());
+        column.add_child(label(&format!("Mana: {}", card.mana_cost)).upcast::<Node>());
+        column.add_child(
+            label(&format!(
+                "Attack: {}  Defense: {}",
+                card.attack, card.defense
+            ))
+            .upcast::<Node>(),
+        );
+        if let Some(ability) = &card.special_ability {
+            column.add_child(label(&render_ability(ability)).upcast::<Node>());
+        }
+
+        let pivot = Vector2::new(CARD_SIZE.x / 2.0, CARD_SIZE.y);
+        let mut base = self.base_mut();
+        base.add_child(column.upcast::<Node>());
+        base.set_custom_minimum_size(CARD_SIZE);
+        base.set_size(CARD_SIZE);
+        // Turn and grow around the bottom middle, like a card held in a hand
+        base.set_pivot_offset(pivot);
+        if !affordable {
+            base.set_modulate(UNAFFORDABLE_TINT);
+            base.set_tooltip_text("Not enough mana".into());
+        }
+    }
+
+    /// Moves the card to its place in the fan.
+    pub(crate) fn place(&mut self, position: Vector2, rotation: f32) {
+        self.rest_position = position;
+        self.rest_rotation = rotation;
+        self.base_mut().set_position(position);
+        self.base_mut().set_rotation(rotation);
+    }
+
+    fn on_left_button(&mut self, pressed: bool, mouse: Vector2) {
+        if pressed {
+            let grab_offset = mouse - self.base().get_global_position();
+            self.drag = Some((mouse, grab_offset));
+            self.base_mut().set_rotation(0.0);
+            self.base_mut().set_z_index(RAISED_Z_INDEX);
+            return;
+        }
+        let Some((pressed_at, _)) = self.drag.take() else {
+            return;
+        };
+        // Back to the fan either way; the hand redraws itself if the card
+        // gets played
+        self.return_to_hand();
+        let index = self.index as i64;
+        if pressed_at.distance_to(mouse) < CLICK_DISTANCE {
+            self.base_mut()
+                .emit_signal("clicked".into(), &[index.to_variant()]);
+        } else {
+            self.base_mut()
+                .emit_signal("dropped".into(), &[index.to_variant(), mouse.to_variant()]);
+        }
+    }
+
+    fn return_to_hand(&mut self) {
+        let (position, rotation) = (self.rest_position, self.rest_rotation);
+        let mut base = self.base_mut();
+        base.set_position(position);
+        base.set_rotation(rotation);
+        base.set_z_index(0);
+        base.set_scale(Vector2::ONE);
+    }
+
+    fn animate_scale(&mut self, scale: f32) {
+        let card = self.base().clone().upcast::<Object>();
+        if let Some(mut tween) = self.base_mut().create_tween() {
+            tween.tween_property(
+                card,
+                "scale".into(),
+                Variant::from(Vector2::ONE * scale),
+                HOVER_SECONDS,
+            );
+        }
+    }
+}
+
+fn label(text: &str) -> Gd<Label> {
+    let mut label = Label::new_alloc();
+    label.set_text(text.into());
+    label.set_horizontal_alignment(HorizontalAlignment::CENTER);
+    label.set_autowrap_mode(AutowrapMode::WORD_SMART);
+    label.set_mouse_filter(MouseFilter::IGNORE);
+    label
+}
+
+/// The player's hand, fanned out along the bottom of this control. Set
+/// `game_path` to the `DeckbuilderGame` to follow, and `drop_target_path` to
+/// the control cards are dragged onto to play them (usually the enemy). With
+/// no drop target, dragging a card anywhere above the hand plays it.
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct HandView {
+    #[base]
+    base: Base<Control>,
+    #[export]
+    game_path: NodePath,
+    #[export]
+    drop_target_path: NodePath,
+    game: Option<Gd<DeckbuilderGame>>,
+    drop_target: Option<Gd<Control>>,
+}
+
+#[godot_api]
+impl IControl for HandView {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            game_path: NodePath::default(),
+            drop_target_path: NodePath::default(),
+            game: None,
+            drop_target: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        if !self.drop_target_path.is_empty() {
+            let path = self.drop_target_path.clone();
+            self.drop_target = self.base().try_get_node_as::<Control>(path);
+        }
+        if !self.game_path.is_empty() {
+            let path = self.game_path.clone();
+            let game = self.base().try_get_node_as::<DeckbuilderGame>(path);
+            match game {
+                Some(game) => self.follow(game, self.drop_target.clone()),
+                None => godot_error!("HandView: no DeckbuilderGame at {}", self.game_path),
+            }
+        }
+    }
+}
+
+#[godot_api]
+impl HandView {
+    #[func]
+    fn on_game_changed(&mut self) {
+        // The game is still mid-action when it signals, so redraw afterwards
+        self.base_mut().call_deferred("refresh".into(), &[]);
+    }
+
+    /// Rebuilds the cards from the game's current hand.
+    #[func]
+    fn refresh(&mut self) {
+        for child in self.base().get_children().iter_shared() {
+            if let Ok(mut card) = child.try_cast::<CardView>() {
+                card.queue_free();
+            }
+        }
+        let Some(game) = self.game.clone() else {
+            return;
+        };
+        let game = game.bind();
+        let Some(core) = game.core() else {
+            return;
+        };
+        let hand = core.get_hand();
+        let width = self.base().get_size().x;
+        let middle = (hand.len() as f32 - 1.0) / 2.0;
+        for (index, card) in hand.iter().enumerate() {
+            let mut view = CardView::new_alloc();
+            view.bind_mut().show_card(
+                index,
+                card,
+                core.can_afford(card) && core.outcome().is_none(),
+            );
+            let step = index as f32 - middle;
+            let position = Vector2::new(
+                width / 2.0 + step * CARD_SPACING - CARD_SIZE.x / 2.0,
+                step * step * FAN_DROP,
+            );
+            view.bind_mut().place(position, step * FAN_ANGLE);
+            view.connect("clicked".into(), self.base().callable("on_card_clicked"));
+            view.connect("dropped".into(), self.base().callable("on_card_dropped"));
+            self.base_mut().add_child(view.upcast::<Node>());
+        }
+    }
+
+    #[func]
+    fn on_card_clicked(&mut self, index: i64) {
+        self.play(index);
+    }
+
+    #[func]
+    fn on_card_dropped(&mut self, index: i64, position: Vector2) {
+        let on_target = match &self.drop_target {
+            Some(target) => target.get_global_rect().contains_point(position),
+            None => position.y < self.base().get_global_position().y,
+        };
+        if on_target {
+            self.play(index);
+        }
+    }
+}
+
+impl HandView {
+    /// Starts following `game`, for scenes built in Rust rather than with
+    /// `game_path`.
+    pub(crate) fn follow(
+        &mut self,
+        mut game: Gd<DeckbuilderGame>,
+        drop_target: Option<Gd<Control>>,
+    ) {
+        game.connect(
+            "state_changed".into(),
+            self.base().callable("on_game_changed"),
+        );
+        self.game = Some(game);
+        self.drop_target = drop_target;
+        self.base_mut().call_deferred("refresh".into(), &[]);
+    }
+
+    fn play(&mut self, index: i64) {
+        if let Some(game) = &mut self.game {
+            // Deferred so the game doesn't signal back into this hand while
+            // it is still handling the card's input
+            game.call_deferred("play_card".into(), &[index.to_variant()]);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Sets up `logging` inside Godot. Three project settings control it:
+//!
+//! - `deckbuilder/logging/levels`: a level spec like `"warn,game=debug"`.
+//! - `deckbuilder/logging/sinks`: where messages go, any of `godot` (the
+//!   editor's output panel), `stdout` and `file`, separated by commas.
+//! - `deckbuilder/logging/file`: the file for the `file` sink.
+//!
+//! The `DECKBUILDER_LOG` environment variable overrides the levels, and
+//! `DECKBUILDER_LOG_FILE` logs to that file whatever the sinks say, so a run
+//! can be made noisier without touching the project.
+
+use crate::logging::{self, format_message, Category, Level, LogLevels, RotatingFileSink, Sink};
+use godot::classes::ProjectSettings;
+use godot::prelude::*;
+use std::path::PathBuf;
+
+const LEVELS_SETTING: &str = "deckbuilder/logging/levels";
+const SINKS_SETTING: &str = "deckbuilder/logging/sinks";
+const FILE_SETTING: &str = "deckbuilder/logging/file";
+
+/// Per-frame rendering detail would flood the output panel, so it is only
+/// shown when asked for.
+const DEFAULT_LEVELS: &str = "info,rendering=warn,lifecycle=warn";
+const DEFAULT_SINKS: &str = "godot";
+const DEFAULT_FILE: &str = "user://deckbuilder.log";
+
+/// Prints to Godot's output, with errors and warnings raised as such.
+struct GodotSink;
+
+impl Sink for GodotSink {
+    fn write(&mut self, level: Level, category: Category, message: &str) {
+        match level {
+            Level::Error => godot_error!("[{}] {}", category, message),
+            Level::Warn => godot_warn!("[{}] {}", category, message),
+            _ => godot_print!("{}", format_message(level, category, message)),
+        }
+    }
+}
+
+/// Reads the logging settings, adding any that are missing to the project
+/// with their defaults so they show up in the editor.
+pub(crate) fn configure() {
+    let mut settings = ProjectSettings::singleton();
+    let levels_spec = setting(&mut settings, LEVELS_SETTING, DEFAULT_LEVELS);
+    let sinks_spec = setting(&mut settings, SINKS_SETTING, DEFAULT_SINKS);
+    let file = setting(&mut settings, FILE_SETTING, DEFAULT_FILE);
+
+    let levels = logging::levels_from_env().unwrap_or_else(|| {
+        levels_spec.parse().unwrap_or_else(|err| {
+            godot_error!("Ignoring {}: {}", LEVELS_SETTING, err);
+            LogLevels::default()
+        })
+    });
+    logging::set_levels(levels);
+
+    let mut sinks: Vec<Box<dyn Sink>> = Vec::new();
+    let mut log_file = logging::file_from_env();
+    for name in sinks_spec.split(',').map(str::trim) {
+        match name {
+            "godot" => sinks.push(Box::new(GodotSink)),
+            "stdout" => sinks.push(Box::new(logging::StdoutSink)),
+            "file" => {
+                let path = settings.globalize_path(file.as_str().into()).to_string();
+                log_file.get_or_insert(PathBuf::from(path));
+            }
+            "" => {}
+            _ => godot_error!("Unknown log sink \"{}\" in {}", name, SINKS_SETTING),
+        }
+    }
+    if let Some(path) = log_file {
+        match RotatingFileSink::new(
+            &path,
+            RotatingFileSink::DEFAULT_MAX_BYTES,
+            RotatingFileSink::DEFAULT_KEPT_FILES,
+        ) {
+            Ok(sink) => sinks.push(Box::new(sink)),
+            Err(err) => godot_error!("Failed to open log file {}: {}", path.display(), err),
+        }
+    }
+    logging::set_sinks(sinks);
+}
+
+fn setting(settings: &mut Gd<ProjectSettings>, name: &str, default: &str) -> String {
+    if !settings.has_setting(name.into()) {
+        settings.set_setting(name.into(), GString::from(default).to_variant());
+        settings.set_initial_value(name.into(), GString::from(default).to_variant());
+    }
+    settings
+        .get_setting(name.into())
+        .try_to::<GString>()
+        .map_or_else(|_| default.to_string(), |value| value.to_string())
+}
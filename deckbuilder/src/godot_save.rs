@@ -0,0 +1,34 @@
+use crate::save::SaveError;
+use crate::CoreGameState;
+use godot::classes::file_access::ModeFlags;
+use godot::classes::FileAccess;
+use godot::global::Error;
+
+/// Where the game is saved when no other path is given.
+pub const SAVE_FILE: &str = "user://savegame.ron";
+
+/// Writes a save through Godot's file API, so `user://` paths work on every
+/// platform.
+pub fn save_game(path: &str, game: &CoreGameState) -> Result<(), SaveError> {
+    let contents = game.to_save_string()?;
+    let Some(mut file) = FileAccess::open(path.into(), ModeFlags::WRITE) else {
+        return Err(open_error(path));
+    };
+    file.store_string(contents.into());
+    Ok(())
+}
+
+pub fn load_game(path: &str) -> Result<CoreGameState, SaveError> {
+    let source = FileAccess::get_file_as_string(path.into()).to_string();
+    if FileAccess::get_open_error() != Error::OK {
+        return Err(open_error(path));
+    }
+    CoreGameState::from_save_str(&source)
+}
+
+fn open_error(path: &str) -> SaveError {
+    SaveError::Io {
+        path: path.to_string(),
+        message: format!("could not be opened ({:?})", FileAccess::get_open_error()),
+    }
+}
@@ -0,0 +1,346 @@
+//! `VideoPlaylist`, a control that plays a list of videos one after another,
+//! fading each in and out. It replaces the old GDScript playlist, which
+//! cycled through six hand-placed `VideoStreamPlayer` children.
+
+use godot::classes::control::LayoutPreset;
+use godot::classes::{Control, IControl, VideoStream, VideoStreamPlayer};
+use godot::prelude::*;
+
+/// One video and how to play it. Times are in seconds.
+#[derive(Clone)]
+struct PlaylistEntry {
+    stream: Gd<VideoStream>,
+    fade_in: f64,
+    fade_out: f64,
+    /// How much of the video to play; 0 plays all of it.
+    length: f64,
+}
+
+/// Plays `videos` in order, looping back to the first if `looping` is set.
+///
+/// Entry `i` fades in over `fade_in[i]` seconds, fades out over the last
+/// `fade_out[i]` seconds and plays for `lengths[i]` seconds (0 for the whole
+/// video). Entries past the end of those arrays use `default_fade_in`,
+/// `default_fade_out` and the whole video. Videos can also be added from
+/// code with `add_video`.
+///
+/// Two players take turns: while one plays, the other holds the next video
+/// loaded and paused. As the playing video starts fading out, the next one
+/// starts playing and fading in, so the two cross-fade. Videos whose length
+/// isn't known play to their end and the next one fades in after them.
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct VideoPlaylist {
+    #[base]
+    base: Base<Control>,
+    #[export]
+    videos: Array<Gd<VideoStream>>,
+    #[export]
+    fade_in: PackedFloat64Array,
+    #[export]
+    fade_out: PackedFloat64Array,
+    #[export]
+    lengths: PackedFloat64Array,
+    #[export]
+    default_fade_in: f64,
+    #[export]
+    default_fade_out: f64,
+    #[export]
+    looping: bool,
+    #[export]
+    autoplay: bool,
+    entries: Vec<PlaylistEntry>,
+    /// `players[current]` is playing `entries[index]`; the other player has
+    /// the next entry preloaded.
+    players: Vec<Gd<VideoStreamPlayer>>,
+    current: usize,
+    index: usize,
+    playing: bool,
+    fading_out: bool,
+    /// Whether the next video has started fading in alongside the current one.
+    next_started: bool,
+}
+
+#[godot_api]
+impl IControl for VideoPlaylist {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            videos: Array::new(),
+            fade_in: PackedFloat64Array::new(),
+            fade_out: PackedFloat64Array::new(),
+            lengths: PackedFloat64Array::new(),
+            default_fade_in: 0.5,
+            default_fade_out: 0.5,
+            looping: true,
+            autoplay: true,
+            entries: Vec::new(),
+            players: Vec::new(),
+            current: 0,
+            index: 0,
+            playing: false,
+            fading_out: false,
+            next_started: false,
+        }
+    }
+
+    fn ready(&mut self) {
+        for _ in 0..2 {
+            let mut player = VideoStreamPlayer::new_alloc();
+            player.set_expand(true);
+            player.set_anchors_and_offsets_preset(LayoutPreset::FULL_RECT);
+            player.connect(
+                "finished".into(),
+                self.base().callable("on_player_finished"),
+            );
+            self.base_mut().add_child(player.clone().upcast::<Node>());
+            self.players.push(player);
+        }
+
+        let videos = self.videos.iter_shared().collect::<Vec<_>>();
+        for (i, stream) in videos.into_iter().enumerate() {
+            let entry = PlaylistEntry {
+                stream,
+                fade_in: setting(&self.fade_in, i).unwrap_or(self.default_fade_in),
+                fade_out: setting(&self.fade_out, i).unwrap_or(self.default_fade_out),
+                length: setting(&self.lengths, i).unwrap_or(0.0),
+            };
+            self.entries.push(entry);
+        }
+
+        if self.autoplay {
+            self.play_from(0);
+        }
+    }
+
+    fn process(&mut self, _delta: f64) {
+        if !self.playing {
+            return;
+        }
+        let entry = self.entries[self.index].clone();
+        let player = &self.players[self.current];
+        let position = player.get_stream_position();
+        let length = if entry.length > 0.0 {
+            entry.length
+        } else {
+            player.get_stream_length()
+        };
+        let fade_in = ramp(position, entry.fade_in);
+        if length <= 0.0 {
+            // Without a length there's no telling when to fade out, so the
+            // video plays to its `finished` signal
+            set_alpha(&mut self.players[self.current], fade_in);
+            return;
+        }
+
+        let remaining = length - position;
+        if remaining <= entry.fade_out && !self.fading_out {
+            self.fading_out = true;
+            let index = self.index as i64;
+            self.base_mut()
+                .emit_signal("fade_out_started".into(), &[index.to_variant()]);
+            if self.next_index().is_some() {
+                self.next_started = true;
+                self.players[1 - self.current].set_paused(false);
+            }
+        }
+        if remaining <= 0.0 {
+            self.advance();
+            return;
+        }
+        let alpha = fade_in.min(ramp(remaining, entry.fade_out));
+        set_alpha(&mut self.players[self.current], alpha);
+
+        if let Some(next) = self.next_index().filter(|_| self.next_started) {
+            let fade_in = self.entries[next].fade_in;
+            let player = &mut self.players[1 - self.current];
+            let alpha = ramp(player.get_stream_position(), fade_in);
+            set_alpha(player, alpha);
+        }
+    }
+}
+
+#[godot_api]
+impl VideoPlaylist {
+    /// Emitted when a video becomes the current one, with its index in the
+    /// playlist. It may already have been fading in for a while.
+    #[signal]
+    fn video_started(index: i64);
+
+    /// Emitted when a video starts fading out.
+    #[signal]
+    fn fade_out_started(index: i64);
+
+    /// Emitted when a video has finished, just before the next one becomes
+    /// current.
+    #[signal]
+    fn video_finished(index: i64);
+
+    /// Emitted when the last video finishes and the playlist starts over.
+    #[signal]
+    fn playlist_looped();
+
+    /// Emitted when the last video finishes and `looping` is off.
+    #[signal]
+    fn playlist_finished();
+
+    /// Appends a video. Times are in seconds; a `length` of 0 plays all of
+    /// it.
+    #[func]
+    fn add_video(&mut self, stream: Gd<VideoStream>, fade_in: f64, fade_out: f64, length: f64) {
+        self.entries.push(PlaylistEntry {
+            stream,
+            fade_in,
+            fade_out,
+            length,
+        });
+    }
+
+    #[func]
+    fn video_count(&self) -> i64 {
+        self.entries.len() as i64
+    }
+
+    /// Index of the video playing now, or -1 if nothing is playing.
+    #[func]
+    fn current_video(&self) -> i64 {
+        if self.playing {
+            self.index as i64
+        } else {
+            -1
+        }
+    }
+
+    /// Starts playing from the video at `index`.
+    #[func]
+    fn play_from(&mut self, index: i64) {
+        let Some(index) = usize::try_from(index)
+            .ok()
+            .filter(|&index| index < self.entries.len())
+        else {
+            if !self.entries.is_empty() {
+                godot_error!("VideoPlaylist has no video {}", index);
+            }
+            return;
+        };
+        self.stop();
+        self.index = index;
+        self.preload(self.current, index);
+        self.start_current();
+    }
+
+    #[func]
+    fn stop(&mut self) {
+        self.playing = false;
+        self.next_started = false;
+        for player in &mut self.players {
+            player.stop();
+            set_alpha(player, 0.0);
+        }
+    }
+
+    /// Skips to the next video straight away, without fading out.
+    #[func]
+    fn skip(&mut self) {
+        if self.playing {
+            self.advance();
+        }
+    }
+
+    #[func]
+    fn on_player_finished(&mut self) {
+        // Only the current video ending moves the playlist on. The incoming
+        // one can only get there first if it's shorter than the cross-fade.
+        if self.playing && !self.players[self.current].is_playing() {
+            self.advance();
+        }
+    }
+}
+
+impl VideoPlaylist {
+    /// Loads entry `index` into `players[slot]`, paused and hidden.
+    fn preload(&mut self, slot: usize, index: usize) {
+        let stream = self.entries[index].stream.clone();
+        let player = &mut self.players[slot];
+        player.set_stream(stream);
+        player.play();
+        player.set_paused(true);
+        set_alpha(player, 0.0);
+    }
+
+    /// Plays the current player's video, carrying on from wherever it got to
+    /// if it started fading in during the last cross-fade.
+    fn start_current(&mut self) {
+        let fade_in = self.entries[self.index].fade_in;
+        let player = &mut self.players[self.current];
+        player.set_paused(false);
+        let alpha = ramp(player.get_stream_position(), fade_in);
+        set_alpha(player, alpha);
+        self.playing = true;
+        self.fading_out = false;
+        self.next_started = false;
+
+        let index = self.index as i64;
+        self.base_mut()
+            .emit_signal("video_started".into(), &[index.to_variant()]);
+
+        if let Some(next) = self.next_index() {
+            self.preload(1 - self.current, next);
+        }
+    }
+
+    fn next_index(&self) -> Option<usize> {
+        let next = self.index + 1;
+        if next < self.entries.len() {
+            Some(next)
+        } else if self.looping && !self.entries.is_empty() {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the current video and hands over to the next one, which is
+    /// either preloaded or already fading in.
+    fn advance(&mut self) {
+        let finished = self.index as i64;
+        let player = &mut self.players[self.current];
+        player.stop();
+        set_alpha(player, 0.0);
+        self.playing = false;
+        self.base_mut()
+            .emit_signal("video_finished".into(), &[finished.to_variant()]);
+
+        let Some(next) = self.next_index() else {
+            self.base_mut().emit_signal("playlist_finished".into(), &[]);
+            return;
+        };
+        if next == 0 {
+            self.base_mut().emit_signal("playlist_looped".into(), &[]);
+        }
+        self.index = next;
+        self.current = 1 - self.current;
+        self.start_current();
+    }
+}
+
+/// Entry `i`'s value in one of the per-entry settings arrays, if it has one.
+fn setting(values: &PackedFloat64Array, i: usize) -> Option<f64> {
+    values.as_slice().get(i).copied()
+}
+
+/// How far through a fade of `duration` seconds `elapsed` seconds is, from 0
+/// to 1. A fade of no duration is always complete.
+fn ramp(elapsed: f64, duration: f64) -> f32 {
+    if duration <= 0.0 {
+        1.0
+    } else {
+        (elapsed / duration).clamp(0.0, 1.0) as f32
+    }
+}
+
+fn set_alpha(player: &mut Gd<VideoStreamPlayer>, alpha: f32) {
+    let mut modulate = player.get_modulate();
+    modulate.a = alpha;
+    player.set_modulate(modulate);
+}
@@ -0,0 +1,181 @@
+//! Suggesting the player's next play, with a short reason for it.
+
+use crate::actions::GameAction;
+use crate::events::GameOutcome;
+use crate::phase::TurnPhase;
+use crate::policy::{Greedy, Lookahead, PlayerPolicy};
+use crate::status::StatusEffect;
+use crate::CoreGameState;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+
+/// What the player should do next and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Index in the hand of the card to play, or `None` to end the turn.
+    pub card: Option<usize>,
+    pub reason: HintReason,
+}
+
+impl Suggestion {
+    pub fn action(&self) -> GameAction {
+        self.card.map_or(GameAction::EndTurn, GameAction::PlayCard)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintReason {
+    /// Playing it starts a sequence that defeats the enemy this turn.
+    Lethal,
+    /// It blocks `blocked` of the `incoming` damage the current block
+    /// doesn't cover.
+    Blocks {
+        blocked: u32,
+        incoming: u32,
+    },
+    /// It leaves enough mana to play this card afterwards, which a more
+    /// expensive play wouldn't.
+    SavesManaFor(String),
+    Damage(u32),
+    /// None of the above, but the lookahead still rates it best.
+    BestValue,
+    NothingAffordable,
+    NothingWorthPlaying,
+}
+
+impl fmt::Display for HintReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HintReason::Lethal => write!(f, "lethal this turn"),
+            HintReason::Blocks { blocked, incoming } if blocked >= incoming => {
+                write!(f, "blocks the incoming {} damage", incoming)
+            }
+            HintReason::Blocks { blocked, incoming } => {
+                write!(f, "blocks {} of the incoming {} damage", blocked, incoming)
+            }
+            HintReason::SavesManaFor(card) => write!(f, "saves mana for {}", card),
+            HintReason::Damage(amount) => write!(f, "deals {} damage", amount),
+            HintReason::BestValue => write!(f, "the best use of your mana"),
+            HintReason::NothingAffordable => write!(f, "you can't afford any card in your hand"),
+            HintReason::NothingWorthPlaying => write!(f, "no card in your hand helps this turn"),
+        }
+    }
+}
+
+impl CoreGameState {
+    /// The play the `Lookahead` policy recommends, explained. `None` unless
+    /// the game is waiting on the player. The same position always gets the
+    /// same suggestion.
+    pub fn suggest_play(&self) -> Option<Suggestion> {
+        if self.phase != TurnPhase::PlayerMain {
+            return None;
+        }
+        let action = Lookahead::new(self.hint_seed()).choose_action(self);
+        let suggestion = match action {
+            GameAction::PlayCard(index) => Suggestion {
+                card: Some(index),
+                reason: self.explain_play(index),
+            },
+            _ if self.legal_actions().len() > 1 => Suggestion {
+                card: None,
+                reason: HintReason::NothingWorthPlaying,
+            },
+            _ => Suggestion {
+                card: None,
+                reason: HintReason::NothingAffordable,
+            },
+        };
+        Some(suggestion)
+    }
+
+    /// A seed that's the same whenever the game is at the same point, which
+    /// is all the suggestion needs and far cheaper than hashing the state.
+    fn hint_seed(&self) -> u64 {
+        self.seed()
+            ^ u64::from(self.turn_counter).rotate_left(32)
+            ^ (self.actions.len() as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+    }
+
+    fn explain_play(&self, index: usize) -> HintReason {
+        let card = &self.player.hand[index];
+        if self.is_lethal(index) {
+            return HintReason::Lethal;
+        }
+        let unblocked = self
+            .incoming_damage()
+            .saturating_sub(self.player.statuses.get(StatusEffect::Block));
+        if card.defense > 0 && unblocked > 0 {
+            return HintReason::Blocks {
+                blocked: card.defense.min(unblocked),
+                incoming: unblocked,
+            };
+        }
+        if let Some(saved_for) = self.mana_saved_for(index) {
+            return HintReason::SavesManaFor(saved_for);
+        }
+        match self.card_damage(card) {
+            0 => HintReason::BestValue,
+            damage => HintReason::Damage(damage),
+        }
+    }
+
+    /// Whether playing the card at `index` and then `Greedy`'s picks for the
+    /// rest of the turn defeats the enemy.
+    fn is_lethal(&self, index: usize) -> bool {
+        let mut copy = self.rollout_copy(&mut ChaCha8Rng::seed_from_u64(self.seed()));
+        let mut action = GameAction::PlayCard(index);
+        while copy.apply(action).is_ok() && copy.phase == TurnPhase::PlayerMain {
+            action = Greedy.choose_action(&copy);
+            if action == GameAction::EndTurn {
+                break;
+            }
+        }
+        copy.outcome() == Some(GameOutcome::Victory)
+    }
+
+    /// The hardest-hitting other card still affordable after playing the
+    /// card at `index`, if some pricier affordable card would use up the
+    /// mana for it.
+    fn mana_saved_for(&self, index: usize) -> Option<String> {
+        let hand = &self.player.hand;
+        let mana = self.player.current_mana;
+        let left = mana - hand[index].mana_cost;
+        let others = || (0..hand.len()).filter(move |&other| other != index);
+        let saved = others()
+            .filter(|&other| hand[other].mana_cost <= left && self.card_damage(&hand[other]) > 0)
+            .max_by_key(|&other| self.card_damage(&hand[other]))?;
+        let crowded_out = others().any(|other| {
+            other != saved
+                && hand[other].mana_cost <= mana
+                && mana - hand[other].mana_cost < hand[saved].mana_cost
+        });
+        crowded_out.then(|| hand[saved].name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Card, Player};
+
+    #[test]
+    fn a_winning_hand_is_lethal() {
+        let strike = Card {
+            name: "Strike".to_string(),
+            attack: 2,
+            defense: 0,
+            lucky: false,
+            special_ability: None,
+            mana_cost: 1,
+        };
+        let mut game = CoreGameState::with_player(5, Player::with_deck(vec![strike; 10]));
+        // Two strikes finish it, and there's mana for three
+        game.enemy.health = 4;
+        game.enemy.statuses = Default::default();
+
+        let suggestion = game.suggest_play().expect("It's the player's turn");
+        assert!(suggestion.card.is_some());
+        assert_eq!(suggestion.reason, HintReason::Lethal);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::events::{Combatant, GameEvent};
+use crate::status::{StatusEffect, Statuses};
+use crate::CoreGameState;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// One thing an enemy can do on its turn. Enemies pick their next intent at
+/// the start of the player's turn, so it can be shown before the player acts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnemyIntent {
+    /// Attack for the enemy's attack value plus `bonus`.
+    Attack { bonus: u32 },
+    /// Gain `amount` Block, which absorbs damage from the player's cards
+    /// until the enemy's next turn.
+    ShieldUp { amount: u32 },
+    /// Permanently raise the enemy's attack.
+    Buff { attack: u32 },
+    /// Drain mana from the player's next turn.
+    Debuff { mana: u32 },
+    /// Call minions that each deal 1 extra damage whenever the enemy attacks.
+    Summon { minions: u32 },
+    /// Apply a status effect to the player.
+    Inflict { effect: StatusEffect, stacks: u32 },
+    /// Apply a status effect to the enemy itself.
+    Gain { effect: StatusEffect, stacks: u32 },
+}
+
+impl Default for EnemyIntent {
+    fn default() -> Self {
+        EnemyIntent::Attack { bonus: 0 }
+    }
+}
+
+/// How an enemy picks its intents: working through a fixed list in order, or
+/// picking one uniformly at random each turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnemyBehavior {
+    Cycle(Vec<EnemyIntent>),
+    Random(Vec<EnemyIntent>),
+}
+
+impl Default for EnemyBehavior {
+    fn default() -> Self {
+        EnemyBehavior::Cycle(vec![EnemyIntent::default()])
+    }
+}
+
+impl EnemyBehavior {
+    pub fn intents(&self) -> &[EnemyIntent] {
+        match self {
+            EnemyBehavior::Cycle(intents) | EnemyBehavior::Random(intents) => intents,
+        }
+    }
+
+    /// The intent for the `step`th enemy turn of the fight.
+    pub fn next_intent(&self, step: usize, rng: &mut impl Rng) -> EnemyIntent {
+        let intent = match self {
+            EnemyBehavior::Cycle(intents) => intents.get(step % intents.len().max(1)),
+            EnemyBehavior::Random(intents) => intents.choose(rng),
+        };
+        intent.copied().unwrap_or_default()
+    }
+}
+
+impl CoreGameState {
+    /// What the enemy will do when the player ends their turn.
+    pub fn enemy_intent(&self) -> Option<EnemyIntent> {
+        self.enemy_intent
+    }
+
+    /// Damage the enemy's current intent would deal before block is applied,
+    /// with the current status effects on both sides.
+    pub fn incoming_damage(&self) -> u32 {
+        match self.enemy_intent {
+            Some(EnemyIntent::Attack { bonus }) => Statuses::attack_damage(
+                self.enemy.attack + bonus + self.enemy.minions,
+                &self.enemy.statuses,
+                &self.player.statuses,
+            ),
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn choose_enemy_intent(&mut self) {
+        let intent = self
+            .enemy
+            .behavior
+            .next_intent(self.enemy.behavior_step, &mut self.rng);
+        self.enemy.behavior_step += 1;
+        self.enemy_intent = Some(intent);
+        self.log(GameEvent::IntentRevealed {
+            enemy: self.enemy.name.clone(),
+            intent,
+            damage: self.incoming_damage(),
+        });
+    }
+
+    /// Carries out the enemy's intent for this turn.
+    pub(crate) fn act_on_intent(&mut self) {
+        let intent = self.enemy_intent.take().unwrap_or_default();
+        let enemy = self.enemy.name.clone();
+        match intent {
+            EnemyIntent::Attack { bonus } => {
+                if bonus > 0 {
+                    self.log(GameEvent::EnemyPowerSurge {
+                        enemy: enemy.clone(),
+                        bonus,
+                    });
+                }
+                let damage = Statuses::resolve_attack(
+                    self.enemy.attack + bonus + self.enemy.minions,
+                    &mut self.enemy.statuses,
+                    &mut self.player.statuses,
+                );
+                self.log(GameEvent::EnemyAttacked { enemy, damage });
+                let damage = self.absorb_with_block(Combatant::Player, damage);
+                if damage > 0 {
+                    self.damage_player(damage);
+                }
+                self.enemy_taunts("taunts");
+            }
+            EnemyIntent::ShieldUp { amount } => {
+                self.apply_status(Combatant::Enemy, StatusEffect::Block, amount);
+            }
+            EnemyIntent::Buff { attack } => {
+                self.enemy.attack = self.enemy.attack.saturating_add(attack);
+                self.log(GameEvent::EnemyBuffed {
+                    enemy,
+                    attack,
+                    total: self.enemy.attack,
+                });
+            }
+            EnemyIntent::Debuff { mana } => {
+                self.player.mana_drain = self.player.mana_drain.saturating_add(mana);
+                self.log(GameEvent::ManaDrained { enemy, mana });
+            }
+            EnemyIntent::Summon { minions } => {
+                self.enemy.minions = self.enemy.minions.saturating_add(minions);
+                self.log(GameEvent::MinionsSummoned {
+                    enemy,
+                    count: minions,
+                    total: self.enemy.minions,
+                });
+            }
+            EnemyIntent::Inflict { effect, stacks } => {
+                self.apply_status(Combatant::Player, effect, stacks);
+            }
+            EnemyIntent::Gain { effect, stacks } => {
+                self.apply_status(Combatant::Enemy, effect, stacks);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::enemies::EnemyDatabase;
+    use crate::Player;
+
+    fn game_against_random_enemy(seed: u64) -> CoreGameState {
+        let mut enemies = EnemyDatabase::default();
+        enemies
+            .add_source(
+                r#"[(
+                    name: "Ice Imp",
+                    health: 50,
+                    attack: 1,
+                    behavior: Random([
+                        Attack(bonus: 1),
+                        ShieldUp(amount: 2),
+                        Buff(attack: 1),
+                        Debuff(mana: 1),
+                        Summon(minions: 1),
+                        Inflict(effect: Weak, stacks: 1),
+                        Gain(effect: Strength, stacks: 1),
+                    ]),
+                )]"#,
+                "test_enemies.ron",
+            )
+            .unwrap();
+        let enemy = enemies.get("Ice Imp").unwrap();
+        CoreGameState::with_enemy(seed, Player::new(), enemy)
+    }
+
+    /// The intents shown over `turns` turns of only ending the turn.
+    fn intents(seed: u64, turns: usize) -> Vec<EnemyIntent> {
+        let mut game = game_against_random_enemy(seed);
+        let mut intents = Vec::new();
+        for _ in 0..turns {
+            intents.extend(game.enemy_intent());
+            game.apply(GameAction::EndTurn).unwrap();
+        }
+        intents
+    }
+
+    #[test]
+    fn same_seed_picks_the_same_intents() {
+        assert_eq!(intents(11, 8), intents(11, 8));
+        assert_ne!(intents(11, 8), intents(12, 8));
+    }
+
+    #[test]
+    fn enemy_does_what_it_showed() {
+        let mut game = game_against_random_enemy(5);
+        for _ in 0..8 {
+            let shown = game.enemy_intent().expect("An intent is shown every turn");
+            let shown_damage = game.incoming_damage();
+            let events = game.apply(GameAction::EndTurn).unwrap();
+            let acted = events.iter().any(|event| match (shown, event) {
+                (EnemyIntent::Attack { .. }, GameEvent::EnemyAttacked { damage, .. }) => {
+                    *damage == shown_damage
+                }
+                (
+                    EnemyIntent::ShieldUp { amount },
+                    GameEvent::StatusApplied {
+                        target: Combatant::Enemy,
+                        effect: StatusEffect::Block,
+                        stacks,
+                        ..
+                    },
+                ) => *stacks == amount,
+                (EnemyIntent::Buff { attack }, GameEvent::EnemyBuffed { attack: buff, .. }) => {
+                    *buff == attack
+                }
+                (EnemyIntent::Debuff { mana }, GameEvent::ManaDrained { mana: drained, .. }) => {
+                    *drained == mana
+                }
+                (EnemyIntent::Summon { minions }, GameEvent::MinionsSummoned { count, .. }) => {
+                    *count == minions
+                }
+                (
+                    EnemyIntent::Inflict { effect, stacks },
+                    GameEvent::StatusApplied {
+                        target: Combatant::Player,
+                        effect: applied,
+                        stacks: applied_stacks,
+                        ..
+                    },
+                )
+                | (
+                    EnemyIntent::Gain { effect, stacks },
+                    GameEvent::StatusApplied {
+                        target: Combatant::Enemy,
+                        effect: applied,
+                        stacks: applied_stacks,
+                        ..
+                    },
+                ) => *applied == effect && *applied_stacks == stacks,
+                _ => false,
+            });
+            assert!(
+                acted,
+                "{:?} was shown but not carried out: {:?}",
+                shown, events
+            );
+            if game.outcome().is_some() {
+                break;
+            }
+        }
+    }
+}
@@ -14,7 +14,14 @@ use rand::Rng;
 struct MyExtension;
 
 #[gdextension]
-unsafe impl ExtensionLibrary for MyExtension {}
+unsafe impl ExtensionLibrary for MyExtension {
+    fn on_level_init(level: InitLevel) {
+        // Project settings are loaded by the time scenes can be
+        if level == InitLevel::Scene {
+            godot_log::configure();
+        }
+    }
+}
 
 #[derive(GodotClass)]
 #[class(base=Node)]
@@ -25,34 +32,34 @@ pub struct GameManager {
 #[godot_api]
 impl INode for GameManager {
     fn init(base: Base<Node>) -> Self {
-        godot_print!("GameManager initialized!");
+        logging::log_at!(Debug, Lifecycle, "GameManager initialized!");
         Self { base }
     }
     fn ready(&mut self) {
-        godot_print!("GameManager ready() called");
+        logging::log_at!(Debug, Lifecycle, "GameManager ready() called");
         let my_player = MyPlayer::new_alloc();
-        godot_print!("MyPlayer allocated: {:?}", my_player);
+        logging::log_at!(Debug, Lifecycle, "MyPlayer allocated: {:?}", my_player);
         self.base_mut()
             .add_child(my_player.clone().upcast::<Node>());
-        godot_print!("MyPlayer added as child to GameManager");
+        logging::log_at!(Debug, Lifecycle, "MyPlayer added as child to GameManager");
 
         // Create a one-shot timer for delayed execution
         let mut timer = godot::classes::Timer::new_alloc();
         timer.set_one_shot(true);
         timer.set_autostart(true);
         timer.set_wait_time(0.1); // 100ms delay
-        godot_print!("Timer created: {:?}", timer);
+        logging::log_at!(Debug, Lifecycle, "Timer created: {:?}", timer);
         timer.connect(
             "timeout".into(),
             self.base().callable("search_for_my_player"),
         );
-        godot_print!("Timer connected to search_for_my_player");
+        logging::log_at!(Debug, Lifecycle, "Timer connected to search_for_my_player");
         let timer_clone = timer.clone();
         self.base_mut()
             .add_child(timer_clone.upcast::<godot::classes::Timer>());
-        godot_print!("Timer added as child to GameManager");
+        logging::log_at!(Debug, Lifecycle, "Timer added as child to GameManager");
         timer.start();
-        godot_print!("Timer started");
+        logging::log_at!(Debug, Lifecycle, "Timer started");
     }
 }
 
@@ -60,30 +67,39 @@ impl INode for GameManager {
 impl GameManager {
     #[func]
     fn search_for_my_player(&mut self) {
-        godot_print!("search_for_my_player called");
-        godot_print!("Current node (GameManager): {:?}", self.base().get_name());
+        logging::log_at!(Debug, Lifecycle, "search_for_my_player called");
+        logging::log_at!(
+            Debug,
+            Lifecycle,
+            "Current node (GameManager): {:?}",
+            self.base().get_name()
+        );
 
         for child in self.base().get_children().iter_shared() {
             let child_name = child.get_name().to_string();
-            godot_print!("Child of GameManager: {}", child_name);
+            logging::log_at!(Debug, Lifecycle, "Child of GameManager: {}", child_name);
 
             if child_name.starts_with("@MyPlayer@") {
                 if let Ok(my_player) = child.try_cast::<MyPlayer>() {
-                    godot_print!("MyPlayer found: {:?}", my_player);
+                    logging::log_at!(Debug, Lifecycle, "MyPlayer found: {:?}", my_player);
                     return;
                 }
             }
         }
 
-        godot_print!("MyPlayer not found in children of GameManager");
+        logging::log_at!(
+            Debug,
+            Lifecycle,
+            "MyPlayer not found in children of GameManager"
+        );
     }
 
     #[func]
     fn create_player(&mut self) {
-        godot_print!("Creating new player");
+        logging::log_at!(Debug, Lifecycle, "Creating new player");
         let my_player = MyPlayer::new_alloc();
         self.base_mut().add_child(my_player.upcast::<Node>());
-        godot_print!("New player created and added as child");
+        logging::log_at!(Debug, Lifecycle, "New player created and added as child");
     }
 }
 #[derive(GodotClass)]
@@ -91,18 +107,18 @@ impl GameManager {
 pub struct MyPlayer {
     #[base]
     base: Base<Node2D>,
-    speed: f32,
-    direction: Vector2,
-    velocity: Vector2,
+    body: motion::Body,
+    /// The visible area the last time there was a viewport to ask.
+    area: Option<motion::Aabb>,
     shape: Gd<ColorRect>,
-    boop_player: Option<Gd<AudioStreamPlayer2D>>,
+    boop_player: Option<Gd<godot_audio::SynthPlayer>>,
     hue: f32,
     glow_shader: Gd<ShaderMaterial>,
 }
 #[godot_api]
 impl INode2D for MyPlayer {
     fn init(base: Base<Node2D>) -> Self {
-        godot_print!("MyPlayer initialized!");
+        logging::log_at!(Debug, Lifecycle, "MyPlayer initialized!");
         let mut rect = ColorRect::new_alloc();
         rect.set_size(Vector2::new(200.0, 200.0));
         rect.set_position(Vector2::new(-100.0, -100.0));
@@ -113,21 +129,42 @@ impl INode2D for MyPlayer {
         let mut shader_material = ShaderMaterial::new_gd();
         let mut shader = Shader::new_gd();
         let shader_code = include_str!("glow_shader.gdshader");
-        godot_print!("Shader code length: {}", shader_code.len());
+        logging::log_at!(
+            Debug,
+            Rendering,
+            "Shader code length: {}",
+            shader_code.len()
+        );
         shader.set_code(shader_code.into());
         shader_material.set_shader(shader);
-        godot_print!("Shader set on material: {:?}", shader_material.get_shader());
+        logging::log_at!(
+            Debug,
+            Rendering,
+            "Shader set on material: {:?}",
+            shader_material.get_shader()
+        );
 
         // Apply the shader to the ColorRect
         rect.set_material(shader_material.clone().upcast::<Material>());
-        godot_print!("Material set on ColorRect: {:?}", rect.get_material());
+        logging::log_at!(
+            Debug,
+            Rendering,
+            "Material set on ColorRect: {:?}",
+            rect.get_material()
+        );
         rect.set_clip_contents(false);
 
         Self {
             base,
-            speed: 1000.0,
-            direction: Vector2::new(1.0, 1.0).normalized(),
-            velocity: Vector2::ZERO,
+            body: motion::Body::new(
+                motion::Vec2::ZERO,
+                motion::Vec2::new(1.0, 1.0).normalized() * 1000.0,
+                motion::Aabb::from_position_size(
+                    motion_vec(rect.get_position()),
+                    motion_vec(rect.get_size()),
+                ),
+            ),
+            area: None,
             shape: rect,
             boop_player: None,
             hue: 0.0,
@@ -135,40 +172,39 @@ impl INode2D for MyPlayer {
         }
     }
     fn ready(&mut self) {
-        godot_print!("MyPlayer ready called");
+        logging::log_at!(Debug, Lifecycle, "MyPlayer ready called");
         let shape_clone = self.shape.clone();
-        godot_print!("Shape cloned: {:?}", shape_clone);
+        logging::log_at!(Debug, Lifecycle, "Shape cloned: {:?}", shape_clone);
         self.base_mut()
             .add_child(shape_clone.clone().upcast::<Node>());
-        godot_print!("ColorRect added as child to MyPlayer");
+        logging::log_at!(Debug, Lifecycle, "ColorRect added as child to MyPlayer");
 
         // Create a one-shot timer for delayed execution
         let mut timer = godot::classes::Timer::new_alloc();
         timer.set_one_shot(true);
         timer.set_autostart(true);
         timer.set_wait_time(0.1); // 100ms delay
-        godot_print!("Timer created: {:?}", timer);
+        logging::log_at!(Debug, Lifecycle, "Timer created: {:?}", timer);
         timer.connect(
             "timeout".into(),
             self.base().callable("search_for_color_rect"),
         );
-        godot_print!("Timer connected to search_for_color_rect");
+        logging::log_at!(Debug, Lifecycle, "Timer connected to search_for_color_rect");
         let timer_clone = timer.clone();
         self.base_mut()
             .add_child(timer_clone.upcast::<godot::classes::Timer>());
-        godot_print!("Timer added as child to MyPlayer");
+        logging::log_at!(Debug, Lifecycle, "Timer added as child to MyPlayer");
         timer.start();
-        godot_print!("Timer started");
+        logging::log_at!(Debug, Lifecycle, "Timer started");
 
         self.base_mut().set_process(true);
-        godot_print!("Processing enabled for MyPlayer");
+        logging::log_at!(Debug, Lifecycle, "Processing enabled for MyPlayer");
 
-        // Create AudioStreamPlayer2D
-        let mut audio_player = AudioStreamPlayer2D::new_alloc();
-        audio_player.set_autoplay(false);
+        // Bounce sounds are synthesized on the fly
+        let boop_player = godot_audio::SynthPlayer::new_alloc();
         self.base_mut()
-            .add_child(audio_player.clone().upcast::<Node>());
-        self.boop_player = Some(audio_player);
+            .add_child(boop_player.clone().upcast::<Node>());
+        self.boop_player = Some(boop_player);
     }
     fn process(&mut self, delta: f64) {
         // Update hue
@@ -186,14 +222,23 @@ impl INode2D for MyPlayer {
         self.glow_shader.set_shader_parameter("brightness".into(), Variant::from(brightness_value));
 
         // Print current shader parameters
-        godot_print!(
+        logging::log_at!(
+            Trace,
+            Rendering,
             "Current glow_color parameter: {:?}, Current brightness: {:?}",
             self.glow_shader.get_shader_parameter("glow_color".into()),
             self.glow_shader.get_shader_parameter("brightness".into())
         );
 
-        godot_print!("ColorRect visible: {}", self.shape.is_visible());
-        godot_print!(
+        logging::log_at!(
+            Trace,
+            Rendering,
+            "ColorRect visible: {}",
+            self.shape.is_visible()
+        );
+        logging::log_at!(
+            Trace,
+            Rendering,
             "ColorRect global position: {:?}, size: {:?}",
             self.shape.get_global_position(),
             self.shape.get_size()
@@ -208,110 +253,145 @@ impl INode2D for MyPlayer {
 impl MyPlayer {
     #[func]
     fn search_for_color_rect(&mut self) {
-        godot_print!("search_for_color_rect called");
-        godot_print!("Current node: {:?}", self.base().get_name());
+        logging::log_at!(Debug, Lifecycle, "search_for_color_rect called");
+        logging::log_at!(
+            Debug,
+            Lifecycle,
+            "Current node: {:?}",
+            self.base().get_name()
+        );
 
         for child in self.base().get_children().iter_shared() {
             let child_name = child.get_name().to_string();
-            godot_print!("Child: {}", child_name);
+            logging::log_at!(Debug, Lifecycle, "Child: {}", child_name);
 
             if child_name.starts_with("@ColorRect@") {
                 if let Ok(color_rect) = child.try_cast::<ColorRect>() {
-                    godot_print!("ColorRect found: {:?}", color_rect);
+                    logging::log_at!(Debug, Lifecycle, "ColorRect found: {:?}", color_rect);
                     return;
                 }
             }
         }
 
-        godot_print!("ColorRect not found in children");
+        logging::log_at!(Debug, Lifecycle, "ColorRect not found in children");
     }
 
     fn move_shape(&mut self, delta: f64) {
-        // Update velocity based on direction and speed
-        self.velocity = self.direction * self.speed;
-
-        // Update position
-        let mut new_position = self.base().get_global_position() + (self.velocity * delta as f32);
-
-        // Bounce off screen edges
+        // Between viewports, keep bouncing around the last one
         if let Some(viewport) = self.base().get_viewport() {
-            let size = viewport.get_visible_rect().size;
-            let shape_size = self.shape.get_size();
-
-            if new_position.x < 0.0 || new_position.x + shape_size.x > size.x {
-                self.direction.x *= -1.0;
-                new_position.x = new_position.x.clamp(0.0, size.x - shape_size.x);
-                godot_print!(
-                    "MyPlayer bounced horizontally. New direction: {:?}",
-                    self.direction
-                );
-                self.play_boop();
-            }
-            if new_position.y < 0.0 || new_position.y + shape_size.y > size.y {
-                self.direction.y *= -1.0;
-                new_position.y = new_position.y.clamp(0.0, size.y - shape_size.y);
-                godot_print!(
-                    "MyPlayer bounced vertically. New direction: {:?}",
-                    self.direction
-                );
-                self.play_boop();
-            }
+            let visible = viewport.get_visible_rect();
+            self.area = Some(motion::Aabb::from_position_size(
+                motion_vec(visible.position),
+                motion_vec(visible.size),
+            ));
         }
+        let Some(area) = self.area else {
+            return;
+        };
 
-        // Set the new position
-        self.base_mut().set_global_position(new_position);
+        // The node may have been moved or the rect resized since last frame
+        self.body.position = motion_vec(self.base().get_global_position());
+        self.body.shape = motion::Aabb::from_position_size(
+            motion_vec(self.shape.get_position()),
+            motion_vec(self.shape.get_size()),
+        );
+        let bounces = self.body.step(delta as f32, area);
+
+        let position = Vector2::new(self.body.position.x, self.body.position.y);
+        self.base_mut().set_global_position(position);
+        for bounce in &bounces {
+            logging::log_at!(
+                Debug,
+                Rendering,
+                "MyPlayer bounced off the {} edge. New velocity: {:?}",
+                bounce.wall,
+                bounce.velocity
+            );
+        }
+        // A corner hit is two bounces but one boop
+        if !bounces.is_empty() {
+            self.play_boop();
+        }
     }
 
     fn play_boop(&mut self) {
-        if let Some(audio_player) = &mut self.boop_player {
-            let sample_rate = 44100.0;
-            let duration = 0.1; // 100ms
-
+        if let Some(player) = &mut self.boop_player {
             // Generate a random frequency between 220Hz (A3) and 880Hz (A5)
             let frequency = rand::thread_rng().gen_range(220.0..=880.0);
-
-            let mut buffer = PackedVector2Array::new();
-            for i in 0..(sample_rate * duration) as i32 {
-                let t = i as f32 / sample_rate as f32;
-                let sample = (t * frequency * 2.0 * std::f32::consts::PI).sin();
-                let envelope = 1.0 - (t / duration as f32);
-                buffer.push(Vector2::new(sample * envelope, sample * envelope));
-            }
-
-            let mut audio_stream = AudioStreamGenerator::new_gd();
-            audio_stream.set_mix_rate(sample_rate);
-            audio_stream.set_buffer_length(duration);
-
-            audio_player.set_stream(audio_stream.upcast::<AudioStream>());
-
-            // Play the audio player first
-            audio_player.play();
-
-            // Now we can get the stream playback
-            if let Some(playback) = audio_player.get_stream_playback() {
-                if let Ok(mut generator_playback) =
-                    playback.try_cast::<AudioStreamGeneratorPlayback>()
-                {
-                    // Convert PackedVector2Array to Vec<Vector2>
-                    let buffer_vec = buffer.to_vec();
-                    for frame in buffer_vec.iter() {
-                        generator_playback.push_frame(*frame);
-                    }
-                }
-            }
-
-            godot_print!("Boop played with frequency: {:.2} Hz", frequency);
+            player
+                .bind_mut()
+                .play_preset(synth::SoundPreset::boop(frequency));
+
+            logging::log_at!(
+                Debug,
+                Audio,
+                "Boop played with frequency: {:.2} Hz",
+                frequency
+            );
         }
     }
 }
 
-pub mod tutorial;
+fn motion_vec(vector: Vector2) -> motion::Vec2 {
+    motion::Vec2::new(vector.x, vector.y)
+}
 
-mod logger;
+pub mod tutorial;
 
-use crate::logger::GameLogger;
+pub mod actions;
+pub mod cards;
+pub mod data;
+pub mod enemies;
+pub mod events;
+mod godot_audio;
+mod godot_battle;
+mod godot_data;
+mod godot_game;
+mod godot_hand;
+mod godot_log;
+mod godot_save;
+mod godot_video;
+pub mod hints;
+pub mod intents;
+pub mod log_export;
+pub mod logger;
+pub mod logging;
+pub mod motion;
+pub mod phase;
+pub mod policy;
+pub mod render;
+pub mod replay;
+pub mod run;
+pub mod save;
+pub mod status;
+pub mod synth;
+mod undo;
+
+use crate::actions::{ActionError, GameAction};
+use crate::cards::CardDatabase;
+use crate::enemies::{EnemyDatabase, EnemyDefinition, EnemyLine, EnemyReactions};
+use crate::events::{Combatant, DrawSource, GameEvent, GameOutcome, TurnEvent};
+use crate::intents::{EnemyBehavior, EnemyIntent};
+use crate::logger::{GameLogger, LogCursor, LogEntry, LogFilter};
+use crate::phase::TurnPhase;
+use crate::render::render_event;
+use crate::status::{StatusEffect, Statuses};
+use crate::undo::History;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Cards the player holds after drawing at the start of each turn.
+pub const HAND_SIZE: usize = 5;
+
+/// The built-in enemy a game is against unless another is chosen.
+pub const DEFAULT_ENEMY: &str = "Mountain Sentinel";
 
+/// The player's health at the start of a game or run.
+pub const STARTING_HEALTH: u32 = 30;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Player {
     pub deck: Vec<Card>,
     pub hand: Vec<Card>,
@@ -319,85 +399,90 @@ pub struct Player {
     pub max_mana: u32,
     pub current_mana: u32,
     pub health: u32,
+    /// The most health healing can bring the player back up to.
+    pub max_health: u32,
+    pub statuses: Statuses,
+    /// Mana taken off the player's next turn by enemy debuffs.
+    pub mana_drain: u32,
 }
 impl Player {
+    /// A new player with the starter deck from the built-in card data.
     pub fn new() -> Self {
-        let mut deck = vec![
-            Card {
-                name: "Mountain Strike".to_string(),
-                attack: 3,
-                defense: 1,
-                lucky: false,
-                special_ability: None,
-                mana_cost: 1,
-            },
-            Card {
-                name: "Stone Shield".to_string(),
-                attack: 1,
-                defense: 3,
-                lucky: false,
-                special_ability: None,
-                mana_cost: 1,
-            },
-            Card {
-                name: "Avalanche".to_string(),
-                attack: 5,
-                defense: 0,
-                lucky: false,
-                special_ability: Some(SpecialAbility::SummonAvalanche(2)),
-                mana_cost: 3,
-            },
-        ];
-        deck.extend(deck.clone());
-        deck.extend(deck.clone());
+        Self::with_deck(CardDatabase::builtin().starter_deck())
+    }
 
+    pub fn with_deck(deck: Vec<Card>) -> Self {
         Player {
             deck,
             hand: Vec::new(),
             discard_pile: Vec::new(),
-            max_mana: 10,
-            current_mana: 10,
-            health: 30,
+            max_mana: 3,
+            current_mana: 3,
+            health: STARTING_HEALTH,
+            max_health: STARTING_HEALTH,
+            statuses: Statuses::default(),
+            mana_drain: 0,
         }
     }
 
     pub fn restore_mana(&mut self) {
-        self.current_mana = self.max_mana;
+        self.current_mana = self.max_mana.saturating_sub(self.mana_drain);
+        self.mana_drain = 0;
     }
 
-    pub fn play_card(&mut self, card_index: usize) -> Option<Card> {
-        if card_index < self.hand.len() {
-            let card = &self.hand[card_index];
-            if self.current_mana >= card.mana_cost {
-                let card = self.hand.remove(card_index);
-                self.current_mana -= card.mana_cost;
-                Some(card)
-            } else {
-                None
-            }
-        } else {
-            None
+    pub fn play_card(&mut self, card_index: usize) -> Result<Card, ActionError> {
+        let card = self
+            .hand
+            .get(card_index)
+            .ok_or(ActionError::InvalidCardIndex {
+                index: card_index,
+                hand_size: self.hand.len(),
+            })?;
+        if self.current_mana < card.mana_cost {
+            return Err(ActionError::NotEnoughMana {
+                required: card.mana_cost,
+                available: self.current_mana,
+            });
         }
+        let card = self.hand.remove(card_index);
+        self.current_mana -= card.mana_cost;
+        Ok(card)
     }
 
     // ... existing methods ...
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SpecialAbility {
     Heal(u32),
     DrawCards(u32),
     ApplyPoison(u32),
     StunEnemy(u32),
+    /// Applies stacks of a status effect to the enemy.
+    Inflict(StatusEffect, u32),
+    /// Gives the player stacks of a status effect.
+    Gain(StatusEffect, u32),
 
     SummonAvalanche(u32),
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CoreGameState {
     pub player: Player,
     pub enemy: Enemy,
     logger: GameLogger,
     turn_counter: u32,
+    rng: ChaCha8Rng,
+    seed: u64,
+    conceded: bool,
+    phase: TurnPhase,
+    enemy_intent: Option<EnemyIntent>,
+    /// Every action accepted by `apply`, in order, for replays.
+    actions: Vec<GameAction>,
+    /// Undo/redo snapshots for the current turn. Not saved, so a loaded game
+    /// starts with nothing to undo.
+    #[serde(skip)]
+    history: History,
 }
 
 impl CoreGameState {
@@ -405,48 +490,72 @@ impl CoreGameState {
         self.turn_counter
     }
 
-    pub fn handle_turn_events(&mut self) {
-        let event = rand::random::<f32>();
+    /// The seed this game's RNG was created from; passing it to `with_seed`
+    /// replays the same draws, events and taunts for the same inputs.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn handle_turn_events(&mut self) {
+        let event = self.rng.gen::<f32>();
 
         if event < 0.1 {
-            self.log("A sudden gust of wind sweeps across the battlefield!".to_string());
-            self.player.health = self.player.health.saturating_add(1);
-            self.enemy.health = self.enemy.health.saturating_add(1);
-            self.log("Both you and the enemy recover 1 health.".to_string());
+            self.log(GameEvent::TurnEventTriggered(TurnEvent::GustOfWind));
+            self.heal_player(1);
+            self.heal_enemy(1);
         } else if event < 0.2 {
-            self.log("The ground trembles beneath your feet!".to_string());
-            let damage = rand::random::<u32>() % 3 + 1;
-            self.player.health = self.player.health.saturating_sub(damage);
-            self.enemy.health = self.enemy.health.saturating_sub(damage);
-            self.log(format!("Both you and the enemy take {} damage.", damage));
+            self.log(GameEvent::TurnEventTriggered(TurnEvent::GroundTremor));
+            let damage = self.rng.gen_range(1..=3);
+            self.environment_damage(Combatant::Player, damage);
+            self.environment_damage(Combatant::Enemy, damage);
         } else if event < 0.3 {
-            self.log("A mysterious energy fills the air...".to_string());
+            self.log(GameEvent::TurnEventTriggered(TurnEvent::MysteriousEnergy));
             self.player.max_mana = self.player.max_mana.saturating_add(1);
             self.player.current_mana = self.player.current_mana.saturating_add(1);
-            self.log("Your maximum mana increases by 1!".to_string());
+            self.log(GameEvent::MaxManaIncreased { amount: 1 });
         } else if event < 0.4 {
-            self.log("The mountain's ancient power surges through you!".to_string());
-            if let Some(card) = self.draw_card() {
-                self.log(format!("You draw an extra card: {}", card.name));
-            }
+            self.log(GameEvent::TurnEventTriggered(TurnEvent::AncientPower));
+            self.draw_card_from(DrawSource::TurnEvent);
         }
     }
 
-    pub fn increment_turn(&mut self) {
+    fn increment_turn(&mut self) {
         self.turn_counter += 1;
-        self.log(format!("Turn {} begins", self.turn_counter));
+        self.log(GameEvent::TurnStarted {
+            turn: self.turn_counter,
+        });
     }
 
-    pub fn log(&mut self, message: String) {
-        self.logger.add_entry(message);
+    pub fn log(&mut self, event: GameEvent) {
+        self.logger.add_entry(self.turn_counter, event);
     }
 
-    pub fn get_log(&self) -> &[String] {
+    pub fn get_log(&self) -> &[LogEntry] {
         self.logger.get_log()
     }
 
+    /// The log as console text, one line per event.
+    pub fn render_log(&self) -> Vec<String> {
+        self.get_log().iter().map(LogEntry::text).collect()
+    }
+
+    /// The log entries `filter` matches, in order.
+    pub fn query_log<'a>(&'a self, filter: &'a LogFilter) -> impl Iterator<Item = &'a LogEntry> {
+        self.logger.query(filter)
+    }
+
+    /// A cursor at the end of the log, for `log_since`.
+    pub fn log_cursor(&self) -> LogCursor {
+        self.logger.cursor()
+    }
+
+    /// The entries logged since `cursor`, moving it past them.
+    pub fn log_since(&self, cursor: &mut LogCursor) -> &[LogEntry] {
+        self.logger.since(cursor)
+    }
+
     pub fn add_user_comment(&mut self, comment: String) {
-        self.log(format!("User comment: {}", comment));
+        self.log(GameEvent::UserComment(comment));
     }
 
     pub fn get_hand(&self) -> &[Card] {
@@ -461,55 +570,164 @@ impl CoreGameState {
         self.enemy.health
     }
 
-    pub fn check_game_over(&self) -> Option<String> {
-        if self.player.health == 0 {
-            Some("Game Over: You have been defeated!".to_string())
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        if self.conceded {
+            Some(GameOutcome::Conceded)
+        } else if self.player.health == 0 {
+            Some(GameOutcome::Defeat)
         } else if self.enemy.health == 0 {
-            Some("Congratulations! You have defeated the enemy!".to_string())
+            Some(GameOutcome::Victory)
         } else {
             None
         }
     }
+
+    pub fn check_game_over(&self) -> Option<String> {
+        self.outcome()
+            .map(|outcome| render_event(&GameEvent::GameOver(outcome)))
+    }
+
+    fn check_for_game_over(&mut self) {
+        if let Some(outcome) = self.outcome() {
+            let already_logged = self
+                .get_log()
+                .iter()
+                .any(|entry| matches!(entry.event, GameEvent::GameOver(_)));
+            if !already_logged {
+                self.log(GameEvent::GameOver(outcome));
+            }
+        }
+    }
+
+    fn enemy_says(&mut self, verb: &str, line: &str) {
+        self.log(GameEvent::EnemyTaunt {
+            enemy: self.enemy.name.clone(),
+            verb: verb.to_string(),
+            line: line.to_string(),
+        });
+    }
+
+    /// Speaks every line of one of the enemy's data-defined reactions.
+    fn enemy_reacts(&mut self, reaction: impl Fn(&EnemyReactions) -> &[EnemyLine]) {
+        for line in reaction(&self.enemy.reactions).to_vec() {
+            self.enemy_says(&line.verb, &line.line);
+        }
+    }
+
+    pub(crate) fn enemy_taunts(&mut self, verb: &str) {
+        let line = self.enemy.taunt(&mut self.rng).to_string();
+        self.enemy_says(verb, &line);
+    }
+
+    pub(crate) fn damage_player(&mut self, amount: u32) {
+        let health_before = self.player.health;
+        self.player.health = self.player.health.saturating_sub(amount);
+        self.log(GameEvent::DamageDealt {
+            target: Combatant::Player,
+            amount: health_before - self.player.health,
+            health_before,
+            health_after: self.player.health,
+        });
+    }
+
+    /// Damage from a battlefield event, which hits either side alike and
+    /// ignores block.
+    fn environment_damage(&mut self, target: Combatant, amount: u32) {
+        let health = match target {
+            Combatant::Player => &mut self.player.health,
+            Combatant::Enemy => &mut self.enemy.health,
+        };
+        let health_before = *health;
+        *health = health.saturating_sub(amount);
+        let health_after = *health;
+        self.log(GameEvent::EnvironmentDamage {
+            target,
+            amount: health_before - health_after,
+            health_before,
+            health_after,
+        });
+    }
+
+    fn heal_player(&mut self, amount: u32) {
+        let health_before = self.player.health;
+        // Never past max health, nor down to it if already above
+        let cap = self.player.max_health.max(health_before);
+        self.player.health = health_before.saturating_add(amount).min(cap);
+        self.log(GameEvent::Healed {
+            target: Combatant::Player,
+            amount: self.player.health - health_before,
+            health_before,
+            health_after: self.player.health,
+        });
+    }
+
+    fn heal_enemy(&mut self, amount: u32) {
+        let health_before = self.enemy.health;
+        let cap = self.enemy.max_health.max(health_before);
+        self.enemy.health = health_before.saturating_add(amount).min(cap);
+        self.log(GameEvent::Healed {
+            target: Combatant::Enemy,
+            amount: self.enemy.health - health_before,
+            health_before,
+            health_after: self.enemy.health,
+        });
+    }
+
+    fn damage_enemy(&mut self, amount: u32) -> u32 {
+        let health_before = self.enemy.health;
+        let damage_dealt = self.enemy.take_damage(amount);
+        self.log(GameEvent::DamageDealt {
+            target: Combatant::Enemy,
+            amount: damage_dealt,
+            health_before,
+            health_after: self.enemy.health,
+        });
+        damage_dealt
+    }
 }
 
 impl CoreGameState {
     pub fn draw_card(&mut self) -> Option<Card> {
         if self.player.deck.is_empty() {
             self.player.deck.append(&mut self.player.discard_pile);
-            self.player.deck.shuffle(&mut rand::thread_rng());
+            self.player.deck.shuffle(&mut self.rng);
         }
         self.player.deck.pop().map(|card| {
             self.player.hand.push(card.clone());
             card
         })
     }
-    pub fn handle_combat(&mut self, card: &Card) -> String {
-        let enemy_health_before = self.enemy.health;
-        let player_health_before = self.player.health;
 
+    /// Draws a card and records a `CardDrawn` event for it.
+    pub fn draw_card_from(&mut self, source: DrawSource) -> Option<Card> {
+        let card = self.draw_card()?;
+        self.log(GameEvent::CardDrawn {
+            card: card.clone(),
+            source,
+        });
+        Some(card)
+    }
+
+    /// Resolves a played card. The enemy no longer strikes back per card; it
+    /// attacks once in its own turn, and card defense becomes block against
+    /// that attack, so each extra card played is pure upside paid for in mana.
+    pub fn handle_combat(&mut self, card: &Card) {
         // Apply card effects
-        let mut damage_dealt = self.enemy.take_damage(card.attack);
-        self.player.health = self.player.health.saturating_add(card.defense);
+        let damage = Statuses::resolve_attack(
+            card.attack,
+            &mut self.player.statuses,
+            &mut self.enemy.statuses,
+        );
+        let damage = self.absorb_with_block(Combatant::Enemy, damage);
+        self.damage_enemy(damage);
+        if card.defense > 0 {
+            self.apply_status(Combatant::Player, StatusEffect::Block, card.defense);
+        }
 
         // Handle special ability if present
         if let Some(ability) = &card.special_ability {
-            damage_dealt += self.handle_special_ability(ability);
-        }
-
-        // Enemy counterattack
-        if self.enemy.health > 0 {
-            let damage_taken = self.enemy.attack.saturating_sub(card.defense);
-            self.player.health = self.player.health.saturating_sub(damage_taken);
+            self.handle_special_ability(ability);
         }
-
-        format!(
-            "You dealt {} damage. Enemy health: {} -> {}. Your health: {} -> {}.",
-            damage_dealt,
-            enemy_health_before,
-            self.enemy.health,
-            player_health_before,
-            self.player.health
-        )
     }
 
     // ... existing methods ...
@@ -517,200 +735,202 @@ impl CoreGameState {
     pub fn handle_special_ability(&mut self, ability: &SpecialAbility) -> u32 {
         match ability {
             SpecialAbility::Heal(amount) => {
-                let heal_amount = self.player.health.saturating_add(*amount) - self.player.health;
-                self.player.health += heal_amount;
-                self.log(format!("Player healed for {} health", heal_amount));
-                self.log(format!(
-                    "{} scoffs: \"Your pitiful healing won't save you!\"",
-                    self.enemy.name
-                ));
+                self.heal_player(*amount);
+                self.enemy_reacts(|reactions| &reactions.heal);
                 0
             }
             SpecialAbility::DrawCards(amount) => {
                 for _ in 0..*amount {
-                    if let Some(card) = self.draw_card() {
-                        self.log(format!("Player drew: {}", card.name));
-                    }
+                    self.draw_card_from(DrawSource::Ability);
                 }
-                self.log(format!(
-                    "{} taunts: \"Draw all you want, it won't change your fate!\"",
-                    self.enemy.name
-                ));
+                self.enemy_reacts(|reactions| &reactions.draw_cards);
                 0
             }
             SpecialAbility::ApplyPoison(amount) => {
-                self.enemy.apply_poison(*amount);
-                self.log(format!("Applied {} poison to the enemy", amount));
+                self.apply_status(Combatant::Enemy, StatusEffect::Poison, *amount);
+                self.enemy_reacts(|reactions| &reactions.apply_poison);
                 0
             }
             SpecialAbility::StunEnemy(duration) => {
                 self.enemy.apply_stun(*duration);
-                self.log(format!("Stunned the enemy for {} turns", duration));
+                self.log(GameEvent::Stunned { turns: *duration });
+                self.enemy_reacts(|reactions| &reactions.stun_enemy);
+                0
+            }
+            SpecialAbility::Inflict(effect, stacks) => {
+                self.apply_status(Combatant::Enemy, *effect, *stacks);
+                0
+            }
+            SpecialAbility::Gain(effect, stacks) => {
+                self.apply_status(Combatant::Player, *effect, *stacks);
                 0
             }
             SpecialAbility::SummonAvalanche(damage) => {
-                let actual_damage = self.enemy.take_damage(*damage);
-                self.log(format!(
-                    "You summon an avalanche, dealing {} damage to the enemy!",
-                    actual_damage
-                ));
-                self.log(format!(
-                    "{} roars: \"Your pathetic avalanche is nothing compared to my mountain-forged armor!\"",
-                    self.enemy.name
-                ));
-                actual_damage
+                // The avalanche is its own hit: Vulnerable and block apply,
+                // but the player's Strength and Weak don't
+                let damage = Statuses::resolve_attack(
+                    *damage,
+                    &mut Statuses::default(),
+                    &mut self.enemy.statuses,
+                );
+                self.log(GameEvent::AvalancheSummoned { damage });
+                let damage = self.absorb_with_block(Combatant::Enemy, damage);
+                let damage_dealt = self.damage_enemy(damage);
+                self.enemy_reacts(|reactions| &reactions.summon_avalanche);
+                damage_dealt
             }
         }
     }
     pub fn new() -> Self {
-        let enemy = Enemy::new(
-            "Mountain Sentinel".to_string(),
-            20,
-            2,
-            vec![
-                "Your primitive tactics are no match for my ancient strength!".to_string(),
-                "Prepare to be crushed beneath the weight of these peaks, human!".to_string(),
-                "Your defeat is as certain as the eternal snow on these mountaintops!".to_string(),
-                "Initiating avalanche protocols...".to_string(),
-                "Your extinction is inevitable. Surrender now and become one with the mountain!"
-                    .to_string(),
-            ],
-        );
-        let enemy_stats = format!(
-            "Enemy stats: {} - Health = {}, Attack = {}",
-            enemy.name, enemy.health, enemy.attack
-        );
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_player(seed, Player::new())
+    }
+
+    /// Starts a game with a given player, e.g. one whose deck was built from
+    /// a loaded `CardDatabase`, against the default enemy.
+    pub fn with_player(seed: u64, player: Player) -> Self {
+        let enemies = EnemyDatabase::builtin();
+        let enemy = enemies
+            .get(DEFAULT_ENEMY)
+            .expect("Built-in enemy data contains the default enemy");
+        Self::with_enemy(seed, player, enemy)
+    }
+
+    /// Starts a game against any enemy definition, e.g. one looked up by
+    /// name in a loaded `EnemyDatabase`.
+    pub fn with_enemy(seed: u64, player: Player, definition: &EnemyDefinition) -> Self {
+        let enemy = definition.to_enemy();
+        let enemy_stats = GameEvent::EnemyAppeared {
+            name: enemy.name.clone(),
+            health: enemy.health,
+            attack: enemy.attack,
+        };
 
         let mut core_state = Self {
-            player: Player::new(),
+            player,
             enemy,
             logger: GameLogger::new(),
             turn_counter: 0,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            seed,
+            conceded: false,
+            phase: TurnPhase::StartOfTurn,
+            enemy_intent: None,
+            actions: Vec::new(),
+            history: History::default(),
         };
 
-        core_state.log("The crisp mountain air suddenly turns electric, a surge of cosmic energy rippling through the ancient peaks!".to_string());
-        core_state.log("The rugged landscape itself seems to warp and bend as an otherworldly presence begins to materialize...".to_string());
-        core_state.log(format!("With a thunderous roar that echoes off the mountain walls, the {} emerges from a portal torn into the fabric of reality! Its form, a terrifying fusion of advanced alien technology and raw, destructive power, looms against the backdrop of snow-capped summits.", core_state.enemy.name));
-        core_state.log("Rocks crumble from nearby cliffs, and you feel the weight of impending doom pressing down on you, as heavy as the mountains themselves.".to_string());
-        core_state.log(format!("The {}'s eyes, glowing with an eerie red light that outshines even the setting sun, scan you coldly. You feel as if your very soul is being analyzed.", core_state.enemy.name));
-        core_state.log("The thin mountain air grows thick with tension, humming with the weight of the coming battle.".to_string());
-        core_state.log(format!("{}'s cybernetic eyes pulse with an unholy red glow, scanning you with cold, calculated malice that seems to freeze the very air around you.", core_state.enemy.name));
+        for line in &definition.intro {
+            core_state.narrate(definition.fill_name(line));
+        }
         core_state.log(enemy_stats);
-        core_state.log(format!(
-            "Standing tall against the mountainous backdrop, {}'s glowing red eyes fix upon you as it unleashes a chilling declaration:",
-            core_state.enemy.name
-        ));
-        core_state.log(format!("\"{}\"", core_state.enemy.taunt()));
-        core_state.log("The very rocks beneath your feet seem to tremble. Here, amidst the towering peaks, the battle for the fate of your world begins NOW!".to_string());
-
-        // Add player introduction
-        core_state.log("\nYou stand at the edge of a narrow mountain pass, the wind whipping around you, carrying the scent of snow and distant pine forests.".to_string());
-        core_state.log("Your breath mists in the cold air as you face the Mountain Sentinel, your hand instinctively reaching for your deck of mystical cards.".to_string());
-        core_state.log("The ancient spirits of the mountains seem to whisper encouragement as you prepare to defend your world from this otherworldly threat.".to_string());
+        if let Some(declaration) = &definition.declaration {
+            core_state.narrate(definition.fill_name(declaration));
+            core_state.enemy_taunts("declares");
+        }
+        for line in &definition.battle_start {
+            core_state.narrate(definition.fill_name(line));
+        }
 
         // Draw initial hand with enhanced descriptions
-        core_state.log("\nYou draw your initial hand:".to_string());
-        for _ in 0..5 {
-            if let Some(card) = core_state.draw_card() {
-                core_state.log(format!("- {}: A card infused with the power of {} (Attack: {}, Defense: {}, Mana Cost: {})",
-                    card.name,
-                    card.name.to_lowercase(),
-                    card.attack,
-                    card.defense,
-                    card.mana_cost
-                ));
-            }
+        core_state.narrate("\nYou draw your initial hand:".to_string());
+        for _ in 0..HAND_SIZE {
+            core_state.draw_card_from(DrawSource::InitialHand);
         }
 
+        core_state.advance();
         core_state
     }
-    pub fn play_card(&mut self, card_index: i32) -> String {
-        if card_index < 0 || card_index as usize >= self.player.hand.len() {
-            return "Invalid card index".to_string();
-        }
-        if let Some(card) = self.player.play_card(card_index as usize) {
-            let result = self.handle_combat(&card);
-            let mut log_message = format!(
-                "Played card: {} (Mana cost: {}). {}",
-                card.name, card.mana_cost, result
-            );
 
-            if self.enemy.health > 0 {
-                let enemy_reaction =
-                    format!("\n{} reacts: \"{}\"", self.enemy.name, self.enemy.taunt());
-                log_message.push_str(&enemy_reaction);
-            } else {
-                let enemy_defeat = format!(
-                    "\n{} wails: \"Impossible! I cannot be defeated by a mere human!\"",
-                    self.enemy.name
-                );
-                log_message.push_str(&enemy_defeat);
+    fn narrate(&mut self, text: String) {
+        self.log(GameEvent::Narration(text));
+    }
+
+    /// Applies a player action, returning the events it produced. Ending the
+    /// turn runs the enemy's turn and the start of the next one.
+    pub fn apply(&mut self, action: GameAction) -> Result<Vec<GameEvent>, ActionError> {
+        let first_event = self.get_log().len();
+        let before = self.snapshot();
+        let recorded = action.clone();
+        match action {
+            GameAction::Comment(comment) => self.add_user_comment(comment),
+            _ if self.phase == TurnPhase::GameOver => return Err(ActionError::GameOver),
+            _ if self.phase != TurnPhase::PlayerMain => {
+                return Err(ActionError::WrongPhase { phase: self.phase })
             }
+            GameAction::PlayCard(card_index) => self.play_card(card_index)?,
+            GameAction::EndTurn => self.phase = TurnPhase::EnemyTurn,
+            GameAction::Concede => self.conceded = true,
+        }
+        self.actions.push(recorded);
+        self.advance();
+        let events = self.get_log()[first_event..]
+            .iter()
+            .map(|entry| entry.event.clone())
+            .collect::<Vec<_>>();
+        self.update_history(before, &events);
+        Ok(events)
+    }
+
+    fn play_card(&mut self, card_index: usize) -> Result<(), ActionError> {
+        let card = self.player.play_card(card_index)?;
+        self.log(GameEvent::CardPlayed { card: card.clone() });
+        self.handle_combat(&card);
+        self.player.discard_pile.push(card);
 
-            self.log(log_message.clone());
-            log_message
+        if self.enemy.health > 0 {
+            self.enemy_taunts("reacts");
         } else {
-            "Not enough mana to play this card".to_string()
+            if let Some(defeat) = self.enemy.defeat.clone() {
+                self.enemy_says(&defeat.verb, &defeat.line);
+            }
         }
+        Ok(())
     }
 
-    pub fn enemy_turn(&mut self) {
-        self.handle_turn_events();
-
-        let poison_damage = self.enemy.take_poison_damage();
-        if poison_damage > 0 {
-            self.log(format!(
-                "{} took {} poison damage",
-                self.enemy.name, poison_damage
-            ));
-            self.log(format!(
-                "{} hisses: \"Your toxins are mere annoyances to my superior systems!\"",
-                self.enemy.name
-            ));
+    fn enemy_turn(&mut self) {
+        if self.tick_statuses(Combatant::Enemy) > 0 {
+            self.enemy_reacts(|reactions| &reactions.poison_tick);
         }
         if self.enemy.health > 0 {
             if self.enemy.is_stunned() {
-                self.log(format!("{} is stunned and cannot attack!", self.enemy.name));
-                self.log(format!(
-                    "{} growls: \"This... delay... changes nothing!\"",
-                    self.enemy.name
-                ));
+                // A stunned enemy loses whatever it was about to do
+                self.enemy_intent = None;
+                self.log(GameEvent::TurnSkipped {
+                    enemy: self.enemy.name.clone(),
+                });
+                self.enemy_reacts(|reactions| &reactions.stunned);
             } else {
-                let mut damage = self.enemy.attack;
-
-                // Turn-based event: Enemy power surge
-                if self.turn_counter % 3 == 0 {
-                    damage += 1;
-                    self.log(format!(
-                        "{} surges with power, increasing its attack!",
-                        self.enemy.name
-                    ));
-                }
-
-                self.player.health = self.player.health.saturating_sub(damage);
-                self.log(format!(
-                    "{} attacks! You take {} damage. Your current health: {}",
-                    self.enemy.name, damage, self.player.health
-                ));
-                self.log(format!(
-                    "{} taunts: \"{}\"",
-                    self.enemy.name,
-                    self.enemy.taunt()
-                ));
+                self.act_on_intent();
             }
         }
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Enemy {
     pub name: String,
     pub health: u32,
+    /// The enemy's starting health, which healing can't go past.
+    pub max_health: u32,
     pub attack: u32,
-    pub poison: u32,
+    pub statuses: Statuses,
     pub taunts: Vec<String>,
     pub stunned: u32,
-    pub shield: u32,
     pub rage: u32,
+    /// Rage needed before the enemy's attack goes up by `rage_attack_bonus`.
+    pub rage_threshold: u32,
+    pub rage_attack_bonus: u32,
+    pub defeat: Option<EnemyLine>,
+    pub reactions: EnemyReactions,
+    pub behavior: EnemyBehavior,
+    /// How many intents the enemy has picked so far this fight.
+    pub behavior_step: usize,
+    /// Summoned minions, each adding 1 damage to the enemy's attacks.
+    pub minions: u32,
 }
 
 impl Enemy {
@@ -718,39 +938,41 @@ impl Enemy {
         Enemy {
             name,
             health,
+            max_health: health,
             attack,
-            poison: 0,
+            statuses: Statuses::default(),
             taunts,
             stunned: 0,
-            shield: 0,
             rage: 0,
+            rage_threshold: 100,
+            rage_attack_bonus: 1,
+            defeat: None,
+            reactions: EnemyReactions::default(),
+            behavior: EnemyBehavior::default(),
+            behavior_step: 0,
+            minions: 0,
         }
     }
 
-    pub fn apply_shield(&mut self, amount: u32) {
-        self.shield = self.shield.saturating_add(amount);
-    }
-
     pub fn increase_rage(&mut self, amount: u32) {
         self.rage = self.rage.saturating_add(amount);
-        if self.rage >= 100 {
-            self.attack += 1;
+        if self.rage >= self.rage_threshold {
+            self.attack += self.rage_attack_bonus;
             self.rage = 0;
         }
     }
 
     pub fn take_damage(&mut self, amount: u32) -> u32 {
-        let damage_after_shield = amount.saturating_sub(self.shield);
-        self.shield = self.shield.saturating_sub(amount);
-        self.health = self.health.saturating_sub(damage_after_shield);
-        self.increase_rage(damage_after_shield);
-        damage_after_shield
+        let health_before = self.health;
+        self.health = self.health.saturating_sub(amount);
+        self.increase_rage(amount);
+        health_before - self.health
     }
 
-    pub fn taunt(&self) -> &str {
+    pub fn taunt(&self, rng: &mut impl Rng) -> &str {
         static DEFAULT_TAUNT: &str = "...";
         self.taunts
-            .choose(&mut rand::thread_rng())
+            .choose(rng)
             .map(String::as_str)
             .unwrap_or(DEFAULT_TAUNT)
     }
@@ -767,23 +989,9 @@ impl Enemy {
             false
         }
     }
-
-    pub fn apply_poison(&mut self, amount: u32) {
-        self.poison += amount;
-    }
-
-    pub fn take_poison_damage(&mut self) -> u32 {
-        let damage = self.poison;
-        self.health = self.health.saturating_sub(damage);
-        self.poison = self.poison.saturating_sub(1);
-        if damage > 0 {
-            println!("{} sizzles: \"Your poison... it burns!\"", self.name);
-        }
-        damage
-    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Card {
     pub name: String,
     pub attack: u32,
@@ -792,3 +1000,45 @@ pub struct Card {
     pub special_ability: Option<SpecialAbility>,
     pub mana_cost: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_and_actions_give_the_same_game() {
+        let actions = [
+            GameAction::PlayCard(0),
+            GameAction::PlayCard(0),
+            GameAction::EndTurn,
+            GameAction::PlayCard(1),
+            GameAction::Comment("halfway".to_string()),
+            GameAction::EndTurn,
+            GameAction::PlayCard(0),
+            GameAction::EndTurn,
+        ];
+        let play = || {
+            let mut game = CoreGameState::with_seed(42);
+            for action in &actions {
+                // Rejected actions (e.g. too little mana) are part of the script too
+                let _ = game.apply(action.clone());
+            }
+            game
+        };
+        let (first, second) = (play(), play());
+        assert_eq!(first.state_hash(), second.state_hash());
+        assert_eq!(first.render_log(), second.render_log());
+    }
+
+    #[test]
+    fn healing_stops_at_max_health() {
+        let mut game = CoreGameState::with_seed(42);
+        game.player.health = game.player.max_health - 2;
+        game.handle_special_ability(&SpecialAbility::Heal(5));
+        assert_eq!(game.player.health, game.player.max_health);
+
+        game.enemy.health = game.enemy.max_health;
+        game.heal_enemy(1);
+        assert_eq!(game.enemy.health, game.enemy.max_health);
+    }
+}
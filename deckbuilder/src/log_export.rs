@@ -0,0 +1,210 @@
+//! Writing the game log out for reading elsewhere: as plain text, as JSON
+//! lines for tools, or as a Markdown match report.
+
+use crate::events::{Combatant, GameEvent, GameOutcome};
+use crate::logger::{LogEntry, LogFilter};
+use crate::CoreGameState;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One line per entry, tagged with its turn and category.
+    Text,
+    /// One JSON object per line, with the event in full.
+    JsonLines,
+    /// A match report: a summary of the game, then the log turn by turn.
+    Markdown,
+}
+
+impl LogFormat {
+    pub const ALL: [LogFormat; 3] = [LogFormat::Text, LogFormat::JsonLines, LogFormat::Markdown];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogFormat::Text => "text",
+            LogFormat::JsonLines => "jsonl",
+            LogFormat::Markdown => "markdown",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LogFormat> {
+        LogFormat::ALL
+            .into_iter()
+            .find(|format| format.name() == name)
+    }
+
+    /// The format a file name's extension asks for: `.jsonl` or `.md`, and
+    /// text for anything else.
+    pub fn for_file(path: &str) -> LogFormat {
+        if path.ends_with(".jsonl") {
+            LogFormat::JsonLines
+        } else if path.ends_with(".md") {
+            LogFormat::Markdown
+        } else {
+            LogFormat::Text
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    turn: u32,
+    timestamp: u64,
+    category: &'static str,
+    text: String,
+    event: &'a GameEvent,
+}
+
+impl CoreGameState {
+    /// The log entries `filter` matches, in `format`.
+    pub fn export_log(&self, format: LogFormat, filter: &LogFilter) -> String {
+        let entries = self.query_log(filter).collect::<Vec<_>>();
+        match format {
+            LogFormat::Text => entries
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "[turn {}, {}] {}\n",
+                        entry.turn,
+                        entry.category.name(),
+                        one_line(&entry.text())
+                    )
+                })
+                .collect(),
+            LogFormat::JsonLines => entries.iter().map(|entry| json_line(entry)).collect(),
+            LogFormat::Markdown => self.match_report(&entries),
+        }
+    }
+
+    fn match_report(&self, entries: &[&LogEntry]) -> String {
+        let result = match self.outcome() {
+            Some(GameOutcome::Victory) => "Victory",
+            Some(GameOutcome::Defeat) => "Defeat",
+            Some(GameOutcome::Conceded) => "Conceded",
+            None => "In progress",
+        };
+        let (mut dealt, mut taken, mut cards) = (0, 0, 0);
+        for entry in self.get_log() {
+            match &entry.event {
+                GameEvent::CardPlayed { .. } => cards += 1,
+                GameEvent::DamageDealt { target, amount, .. }
+                | GameEvent::PoisonTicked { target, amount, .. } => match target {
+                    Combatant::Enemy => dealt += amount,
+                    Combatant::Player => taken += amount,
+                },
+                GameEvent::EnvironmentDamage {
+                    target: Combatant::Player,
+                    amount,
+                    ..
+                } => taken += amount,
+                _ => {}
+            }
+        }
+
+        let mut lines = vec![
+            format!("# Match report: you vs {}", self.enemy.name),
+            String::new(),
+            "| | |".to_string(),
+            "|---|---|".to_string(),
+            format!("| Result | {} |", result),
+            format!("| Turns | {} |", self.get_turn_count()),
+            format!("| Seed | {} |", self.seed()),
+            format!("| Your health | {} |", self.get_player_health()),
+            format!("| Enemy health | {} |", self.get_enemy_health()),
+            format!("| Damage dealt | {} |", dealt),
+            format!("| Damage taken | {} |", taken),
+            format!("| Cards played | {} |", cards),
+        ];
+        let mut turn = None;
+        for entry in entries {
+            if turn != Some(entry.turn) {
+                turn = Some(entry.turn);
+                lines.push(String::new());
+                lines.push(match entry.turn {
+                    0 => "## Setup".to_string(),
+                    turn => format!("## Turn {}", turn),
+                });
+                lines.push(String::new());
+            }
+            lines.push(format!("- {}", one_line(&entry.text())));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+/// Some narration spans several lines, which would break up an entry.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn json_line(entry: &LogEntry) -> String {
+    let json = JsonEntry {
+        turn: entry.turn,
+        timestamp: entry.timestamp,
+        category: entry.category.name(),
+        text: entry.text(),
+        event: &entry.event,
+    };
+    let mut line = serde_json::to_string(&json).expect("Log entries always encode as JSON");
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logger::LogCategory;
+    use crate::GameAction;
+
+    fn game() -> CoreGameState {
+        let mut game = CoreGameState::with_seed(3);
+        game.apply(GameAction::PlayCard(0)).unwrap();
+        game.apply(GameAction::Comment("first   card\nplayed".to_string()))
+            .unwrap();
+        game.apply(GameAction::EndTurn).unwrap();
+        game
+    }
+
+    #[test]
+    fn text_has_a_tagged_line_per_entry() {
+        let game = game();
+        let text = game.export_log(LogFormat::Text, &LogFilter::default());
+        assert_eq!(text.lines().count(), game.get_log().len());
+        assert!(text.lines().all(|line| line.starts_with("[turn ")));
+        assert!(text.contains("[turn 1, comment] User comment: first card played\n"));
+    }
+
+    #[test]
+    fn json_lines_hold_each_entry_in_full() {
+        let game = game();
+        let filter = LogFilter::categories(&[LogCategory::Comment]);
+        let json = game.export_log(LogFormat::JsonLines, &filter);
+        let lines = json.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+
+        let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry["turn"], 1);
+        assert_eq!(entry["category"], "comment");
+        assert_eq!(entry["text"], "User comment: first   card\nplayed");
+        assert_eq!(entry["event"]["UserComment"], "first   card\nplayed");
+    }
+
+    #[test]
+    fn markdown_is_a_match_report() {
+        let game = game();
+        let report = game.export_log(LogFormat::Markdown, &LogFilter::default());
+        assert!(report.starts_with("# Match report: you vs Mountain Sentinel\n"));
+        for row in [
+            "| Result | In progress |",
+            "| Seed | 3 |",
+            "| Cards played | 1 |",
+            "## Setup",
+            "## Turn 1",
+            "## Turn 2",
+            "- User comment: first card played",
+        ] {
+            assert!(report.contains(row), "{} missing from:\n{}", row, report);
+        }
+    }
+}
@@ -1,19 +1,282 @@
+//! The game log: every `GameEvent`, tagged with the turn it happened on, when
+//! it happened and what kind of event it is, so frontends can filter it or
+//! follow along without rereading the whole thing.
+
+use crate::events::GameEvent;
+use crate::logging::log_at;
+use crate::render::render_event;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What kind of thing a log entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogCategory {
+    /// Narration, the enemy's entrance and its taunts.
+    Story,
+    /// Turns starting and the battlefield events they bring.
+    Turn,
+    Cards,
+    /// Damage, healing and block.
+    Combat,
+    Status,
+    /// The enemy's intents and what it does on its turn.
+    Enemy,
+    Comment,
+    Outcome,
+}
+
+impl LogCategory {
+    pub const ALL: [LogCategory; 8] = [
+        LogCategory::Story,
+        LogCategory::Turn,
+        LogCategory::Cards,
+        LogCategory::Combat,
+        LogCategory::Status,
+        LogCategory::Enemy,
+        LogCategory::Comment,
+        LogCategory::Outcome,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogCategory::Story => "story",
+            LogCategory::Turn => "turn",
+            LogCategory::Cards => "cards",
+            LogCategory::Combat => "combat",
+            LogCategory::Status => "status",
+            LogCategory::Enemy => "enemy",
+            LogCategory::Comment => "comment",
+            LogCategory::Outcome => "outcome",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LogCategory> {
+        LogCategory::ALL
+            .into_iter()
+            .find(|category| category.name() == name)
+    }
+
+    pub fn of(event: &GameEvent) -> LogCategory {
+        match event {
+            GameEvent::Narration(_)
+            | GameEvent::EnemyAppeared { .. }
+            | GameEvent::EnemyTaunt { .. } => LogCategory::Story,
+            GameEvent::TurnStarted { .. }
+            | GameEvent::TurnEventTriggered(_)
+            | GameEvent::MaxManaIncreased { .. } => LogCategory::Turn,
+            GameEvent::CardDrawn { .. }
+            | GameEvent::CardPlayed { .. }
+            | GameEvent::HandDiscarded { .. } => LogCategory::Cards,
+            GameEvent::DamageDealt { .. }
+            | GameEvent::EnvironmentDamage { .. }
+            | GameEvent::Healed { .. }
+            | GameEvent::DamageBlocked { .. }
+            | GameEvent::AvalancheSummoned { .. }
+            | GameEvent::PoisonTicked { .. } => LogCategory::Combat,
+            GameEvent::StatusApplied { .. } | GameEvent::Stunned { .. } => LogCategory::Status,
+            GameEvent::TurnSkipped { .. }
+            | GameEvent::EnemyPowerSurge { .. }
+            | GameEvent::IntentRevealed { .. }
+            | GameEvent::EnemyBuffed { .. }
+            | GameEvent::ManaDrained { .. }
+            | GameEvent::MinionsSummoned { .. }
+            | GameEvent::EnemyAttacked { .. } => LogCategory::Enemy,
+            GameEvent::UserComment(_) => LogCategory::Comment,
+            GameEvent::GameOver(_) => LogCategory::Outcome,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// The turn the event happened on; 0 for the setup before the first
+    /// turn.
+    pub turn: u32,
+    /// When the event was logged, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub category: LogCategory,
+    pub event: GameEvent,
+}
+
+impl LogEntry {
+    /// The entry as console text.
+    pub fn text(&self) -> String {
+        render_event(&self.event)
+    }
+}
+
+/// Which entries a query wants. The default matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    pub first_turn: Option<u32>,
+    pub last_turn: Option<u32>,
+    /// Empty matches every category.
+    pub categories: Vec<LogCategory>,
+}
+
+impl LogFilter {
+    /// Entries from one turn.
+    pub fn turn(turn: u32) -> Self {
+        LogFilter {
+            first_turn: Some(turn),
+            last_turn: Some(turn),
+            ..LogFilter::default()
+        }
+    }
+
+    pub fn categories(categories: &[LogCategory]) -> Self {
+        LogFilter {
+            categories: categories.to_vec(),
+            ..LogFilter::default()
+        }
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let after_first = match self.first_turn {
+            Some(first) => entry.turn >= first,
+            None => true,
+        };
+        let before_last = match self.last_turn {
+            Some(last) => entry.turn <= last,
+            None => true,
+        };
+        after_first
+            && before_last
+            && (self.categories.is_empty() || self.categories.contains(&entry.category))
+    }
+}
+
+/// A place in the log, for reading only the entries added since. Undoing
+/// can shorten the log; a cursor left past its end moves back to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogCursor {
+    position: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameLogger {
-    log: Vec<String>,
+    log: Vec<LogEntry>,
+    /// Never passes entries on to `logging`, whatever the game category's
+    /// level is.
+    #[serde(skip)]
+    silent: bool,
 }
 
 impl GameLogger {
     pub fn new() -> Self {
-        Self { log: Vec::new() }
+        Self {
+            log: Vec::new(),
+            silent: false,
+        }
     }
 
-    pub fn add_entry(&mut self, entry: String) {
-        let entry_clone = entry.clone();
-        self.log.push(entry);
-        println!("{}", entry_clone); // Print to console immediately
+    /// A logger for games nobody is watching, like a policy's rollouts.
+    pub fn silent() -> Self {
+        Self {
+            log: Vec::new(),
+            silent: true,
+        }
     }
 
-    pub fn get_log(&self) -> &[String] {
+    pub fn add_entry(&mut self, turn: u32, event: GameEvent) {
+        if !self.silent {
+            log_at!(Debug, Game, "{}", render_event(&event));
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_millis() as u64);
+        self.log.push(LogEntry {
+            turn,
+            timestamp,
+            category: LogCategory::of(&event),
+            event,
+        });
+    }
+
+    pub fn get_log(&self) -> &[LogEntry] {
         &self.log
     }
+
+    pub fn query<'a>(&'a self, filter: &'a LogFilter) -> impl Iterator<Item = &'a LogEntry> {
+        self.log.iter().filter(|entry| filter.matches(entry))
+    }
+
+    /// A cursor at the end of the log, which sees only entries added later.
+    pub fn cursor(&self) -> LogCursor {
+        LogCursor {
+            position: self.log.len(),
+        }
+    }
+
+    /// The entries added since `cursor`, moving it past them.
+    pub fn since(&self, cursor: &mut LogCursor) -> &[LogEntry] {
+        let start = cursor.position.min(self.log.len());
+        cursor.position = self.log.len();
+        &self.log[start..]
+    }
+
+    /// Zeroes every timestamp, so two runs of the same game compare equal.
+    pub(crate) fn clear_timestamps(&mut self) {
+        for entry in &mut self.log {
+            entry.timestamp = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CoreGameState, GameAction};
+
+    fn entry(turn: u32, event: GameEvent) -> LogEntry {
+        LogEntry {
+            turn,
+            timestamp: 0,
+            category: LogCategory::of(&event),
+            event,
+        }
+    }
+
+    #[test]
+    fn filter_matches_turn_range_and_category() {
+        let comment = |turn| entry(turn, GameEvent::UserComment("hm".to_string()));
+        let filter = LogFilter {
+            first_turn: Some(2),
+            last_turn: Some(3),
+            categories: vec![LogCategory::Comment, LogCategory::Outcome],
+        };
+        assert!(!filter.matches(&comment(1)));
+        assert!(filter.matches(&comment(2)));
+        assert!(filter.matches(&comment(3)));
+        assert!(!filter.matches(&comment(4)));
+        assert!(!filter.matches(&entry(2, GameEvent::TurnStarted { turn: 2 })));
+
+        let from_turn_2 = LogFilter {
+            first_turn: Some(2),
+            ..LogFilter::default()
+        };
+        assert!(from_turn_2.matches(&entry(9, GameEvent::TurnStarted { turn: 9 })));
+        assert!(!from_turn_2.matches(&comment(1)));
+        assert!(LogFilter::default().matches(&comment(0)));
+    }
+
+    #[test]
+    fn cursor_after_undo_sees_only_new_entries() {
+        let mut game = CoreGameState::with_seed(3);
+        let mut cursor = game.log_cursor();
+        assert!(game.log_since(&mut cursor).is_empty());
+
+        game.apply(GameAction::PlayCard(0)).unwrap();
+        assert!(!game.log_since(&mut cursor).is_empty());
+
+        // Undoing shortens the log behind the cursor
+        let length = game.get_log().len();
+        game.undo().unwrap();
+        assert!(game.get_log().len() < length);
+        assert!(game.log_since(&mut cursor).is_empty());
+
+        let before = game.get_log().len();
+        game.apply(GameAction::EndTurn).unwrap();
+        assert_eq!(game.log_since(&mut cursor), &game.get_log()[before..]);
+    }
 }
@@ -0,0 +1,517 @@
+//! Diagnostic logging with levels and categories, so the console only shows
+//! what's wanted. Each category has its own level, and every message that
+//! passes goes to all of the configured sinks.
+//!
+//! Levels are set with a spec like `"info"` or `"warn,rendering=trace"`: an
+//! optional default level followed by per-category overrides. The
+//! `DECKBUILDER_LOG` environment variable holds a spec, and
+//! `DECKBUILDER_LOG_FILE` names a file to log to as well. In Godot the same
+//! settings can be made in the project settings; see `godot_log`.
+//!
+//! Until something is configured, everything at `Info` and above is printed
+//! to stdout.
+//!
+//! This is for diagnostics only. What the player needs to see, like the
+//! command-line game's narration, is printed by the frontend itself and
+//! doesn't depend on these levels.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const LEVEL_ENV_VAR: &str = "DECKBUILDER_LOG";
+pub const FILE_ENV_VAR: &str = "DECKBUILDER_LOG_FILE";
+
+/// How important a message is. A category set to a level shows messages at
+/// that level and everything more important; `Off` shows nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    /// Per-frame detail.
+    Trace,
+}
+
+impl Level {
+    pub const ALL: [Level; 6] = [
+        Level::Off,
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Off => "off",
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Level> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// What part of the program a message is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Shaders, colors and anything else drawn every frame.
+    Rendering,
+    Audio,
+    /// The card game itself. Every game log entry is echoed here at `Debug`.
+    Game,
+    /// Nodes being created, added and found.
+    Lifecycle,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::Rendering,
+        Category::Audio,
+        Category::Game,
+        Category::Lifecycle,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Rendering => "rendering",
+            Category::Audio => "audio",
+            Category::Game => "game",
+            Category::Lifecycle => "lifecycle",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Category> {
+        Category::ALL
+            .into_iter()
+            .find(|category| category.name().eq_ignore_ascii_case(name))
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The level for each category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogLevels {
+    levels: [Level; Category::ALL.len()],
+}
+
+impl LogLevels {
+    /// Every category at `level`.
+    pub fn all(level: Level) -> Self {
+        LogLevels {
+            levels: [level; Category::ALL.len()],
+        }
+    }
+
+    pub fn get(&self, category: Category) -> Level {
+        self.levels[category.index()]
+    }
+
+    pub fn set(&mut self, category: Category, level: Level) {
+        self.levels[category.index()] = level;
+    }
+}
+
+impl Default for LogLevels {
+    fn default() -> Self {
+        LogLevels::all(Level::Info)
+    }
+}
+
+impl FromStr for LogLevels {
+    type Err = String;
+
+    /// Parses a spec like `"warn,rendering=trace,audio=off"`. Categories the
+    /// spec doesn't mention get its default level, or `Info` if it has none.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut levels = LogLevels::default();
+        let mut overrides = Vec::new();
+        for part in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let Some((category, level)) = part.split_once('=') else {
+                let level = Level::from_name(part)
+                    .ok_or_else(|| format!("Unknown log level \"{}\"", part))?;
+                levels = LogLevels::all(level);
+                continue;
+            };
+            let category = Category::from_name(category.trim())
+                .ok_or_else(|| format!("Unknown log category \"{}\"", category.trim()))?;
+            let level = Level::from_name(level.trim())
+                .ok_or_else(|| format!("Unknown log level \"{}\"", level.trim()))?;
+            overrides.push((category, level));
+        }
+        // Overrides win over the default wherever they are in the spec
+        for (category, level) in overrides {
+            levels.set(category, level);
+        }
+        Ok(levels)
+    }
+}
+
+/// Somewhere log messages go.
+pub trait Sink: Send {
+    fn write(&mut self, level: Level, category: Category, message: &str);
+}
+
+/// A message as a line of text: plain for `Info`, since that's what the
+/// console shows by default, and tagged with its level and category
+/// otherwise.
+pub fn format_message(level: Level, category: Category, message: &str) -> String {
+    if level == Level::Info {
+        message.to_string()
+    } else {
+        format!("[{} {}] {}", level, category, message)
+    }
+}
+
+/// Prints to stdout.
+pub struct StdoutSink;
+
+impl Sink for StdoutSink {
+    fn write(&mut self, level: Level, category: Category, message: &str) {
+        println!("{}", format_message(level, category, message));
+    }
+}
+
+/// Appends to a file, moving it aside once it grows past a size limit:
+/// `game.log` becomes `game.log.1`, `game.log.1` becomes `game.log.2` and so
+/// on, keeping `kept_files` old files.
+pub struct RotatingFileSink {
+    path: PathBuf,
+    max_bytes: u64,
+    kept_files: u32,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFileSink {
+    pub const DEFAULT_MAX_BYTES: u64 = 1024 * 1024;
+    pub const DEFAULT_KEPT_FILES: u32 = 3;
+
+    pub fn new(path: &Path, max_bytes: u64, kept_files: u32) -> io::Result<Self> {
+        let file = open_for_append(path)?;
+        let written = file.metadata()?.len();
+        Ok(RotatingFileSink {
+            path: path.to_path_buf(),
+            max_bytes,
+            kept_files,
+            file,
+            written,
+        })
+    }
+
+    fn rotated_path(&self, number: u32) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", number));
+        PathBuf::from(path)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.kept_files == 0 {
+            self.file.set_len(0)?;
+        } else {
+            for number in (1..self.kept_files).rev() {
+                let from = self.rotated_path(number);
+                if from.exists() {
+                    fs::rename(from, self.rotated_path(number + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = open_for_append(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn open_for_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Sink for RotatingFileSink {
+    fn write(&mut self, level: Level, category: Category, message: &str) {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |time| time.as_secs_f64());
+        let line = format!("{:.3} [{} {}] {}\n", seconds, level, category, message);
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            if let Err(err) = self.rotate() {
+                eprintln!("Failed to rotate {}: {}", self.path.display(), err);
+            }
+        }
+        // A log that can't be written has nowhere better to report to
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.written += line.len() as u64;
+        }
+    }
+}
+
+/// One level per category, kept outside the sinks' lock so checking whether
+/// a message would be logged is cheap enough to do every frame.
+static LEVELS: [AtomicU8; Category::ALL.len()] = [
+    AtomicU8::new(Level::Info as u8),
+    AtomicU8::new(Level::Info as u8),
+    AtomicU8::new(Level::Info as u8),
+    AtomicU8::new(Level::Info as u8),
+];
+
+/// `None` until sinks are set, meaning stdout.
+static SINKS: Mutex<Option<Vec<Box<dyn Sink>>>> = Mutex::new(None);
+
+fn sinks() -> MutexGuard<'static, Option<Vec<Box<dyn Sink>>>> {
+    // A sink that panicked mid-write leaves nothing that needs cleaning up
+    SINKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn set_levels(levels: LogLevels) {
+    for category in Category::ALL {
+        set_level(category, levels.get(category));
+    }
+}
+
+pub fn set_level(category: Category, level: Level) {
+    LEVELS[category.index()].store(level as u8, Ordering::Relaxed);
+}
+
+pub fn levels() -> LogLevels {
+    let mut levels = LogLevels::default();
+    for category in Category::ALL {
+        let level = LEVELS[category.index()].load(Ordering::Relaxed);
+        levels.set(category, Level::ALL[level as usize]);
+    }
+    levels
+}
+
+/// Replaces every sink. With no sinks, nothing is logged anywhere.
+pub fn set_sinks(new_sinks: Vec<Box<dyn Sink>>) {
+    *sinks() = Some(new_sinks);
+}
+
+/// Whether a message at `level` in `category` would be logged.
+pub fn enabled(level: Level, category: Category) -> bool {
+    level != Level::Off && level as u8 <= LEVELS[category.index()].load(Ordering::Relaxed)
+}
+
+/// Sends a message to every sink if its category's level lets it through.
+/// `log_at!` skips formatting the message when it wouldn't be.
+pub fn log(level: Level, category: Category, message: &str) {
+    if !enabled(level, category) {
+        return;
+    }
+    match &mut *sinks() {
+        Some(sinks) => {
+            for sink in sinks.iter_mut() {
+                sink.write(level, category, message);
+            }
+        }
+        None => StdoutSink.write(level, category, message),
+    }
+}
+
+/// Sets levels from `DECKBUILDER_LOG`, falling back to `default`, and logs
+/// to stdout plus the file in `DECKBUILDER_LOG_FILE` if there is one. For
+/// the command-line tools.
+pub fn configure_from_env(default: LogLevels) {
+    set_levels(levels_from_env().unwrap_or(default));
+    let mut new_sinks: Vec<Box<dyn Sink>> = vec![Box::new(StdoutSink)];
+    if let Some(path) = file_from_env() {
+        match RotatingFileSink::new(
+            &path,
+            RotatingFileSink::DEFAULT_MAX_BYTES,
+            RotatingFileSink::DEFAULT_KEPT_FILES,
+        ) {
+            Ok(sink) => new_sinks.push(Box::new(sink)),
+            Err(err) => eprintln!("Failed to open log file {}: {}", path.display(), err),
+        }
+    }
+    set_sinks(new_sinks);
+}
+
+/// The levels in `DECKBUILDER_LOG`, if it is set and valid.
+pub fn levels_from_env() -> Option<LogLevels> {
+    let spec = std::env::var(LEVEL_ENV_VAR).ok()?;
+    match spec.parse() {
+        Ok(levels) => Some(levels),
+        Err(err) => {
+            eprintln!("Ignoring {}: {}", LEVEL_ENV_VAR, err);
+            None
+        }
+    }
+}
+
+pub fn file_from_env() -> Option<PathBuf> {
+    std::env::var_os(FILE_ENV_VAR)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Logs a `format!`-style message, skipping the formatting when the
+/// category's level would drop it:
+///
+/// `log_at!(Debug, Audio, "Boop played at {:.2} Hz", frequency);`
+macro_rules! log_at {
+    ($level:ident, $category:ident, $($arg:tt)+) => {{
+        let level = $crate::logging::Level::$level;
+        let category = $crate::logging::Category::$category;
+        if $crate::logging::enabled(level, category) {
+            $crate::logging::log(level, category, &format!($($arg)+));
+        }
+    }};
+}
+pub(crate) use log_at;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_and_category_names_parse_back() {
+        for level in Level::ALL {
+            assert_eq!(Level::from_name(level.name()), Some(level));
+        }
+        for category in Category::ALL {
+            assert_eq!(Category::from_name(category.name()), Some(category));
+        }
+        assert_eq!(Level::from_name("WARN"), Some(Level::Warn));
+        assert_eq!(Category::from_name("Audio"), Some(Category::Audio));
+        assert_eq!(Level::from_name("loud"), None);
+        assert_eq!(Category::from_name("physics"), None);
+    }
+
+    #[test]
+    fn spec_sets_a_default_and_per_category_overrides() {
+        let levels: LogLevels = " warn, rendering=trace ,audio=OFF".parse().unwrap();
+        assert_eq!(levels.get(Category::Rendering), Level::Trace);
+        assert_eq!(levels.get(Category::Audio), Level::Off);
+        assert_eq!(levels.get(Category::Game), Level::Warn);
+        assert_eq!(levels.get(Category::Lifecycle), Level::Warn);
+
+        // Overrides apply even before the default, which only sets the rest
+        let levels: LogLevels = "game=debug,error".parse().unwrap();
+        assert_eq!(levels.get(Category::Game), Level::Debug);
+        assert_eq!(levels.get(Category::Audio), Level::Error);
+
+        assert_eq!("".parse(), Ok(LogLevels::default()));
+        let levels: LogLevels = "lifecycle=trace".parse().unwrap();
+        assert_eq!(levels.get(Category::Rendering), Level::Info);
+    }
+
+    #[test]
+    fn spec_with_unknown_names_is_rejected() {
+        assert_eq!(
+            "loud".parse::<LogLevels>(),
+            Err("Unknown log level \"loud\"".to_string())
+        );
+        assert_eq!(
+            "info,physics=debug".parse::<LogLevels>(),
+            Err("Unknown log category \"physics\"".to_string())
+        );
+        assert_eq!(
+            "audio = loud".parse::<LogLevels>(),
+            Err("Unknown log level \"loud\"".to_string())
+        );
+    }
+
+    /// An empty directory of its own for each test's log files.
+    fn log_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "deckbuilder-logging-{}-{}",
+            test,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn messages(path: &Path) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.rsplit("] ").next().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn file_sink_rotates_past_its_size_limit() {
+        let dir = log_dir("rotate");
+        let path = dir.join("game.log");
+        // Room for one line but not two
+        let mut sink = RotatingFileSink::new(&path, 60, 2).unwrap();
+        for message in ["first", "second", "third", "fourth"] {
+            sink.write(Level::Info, Category::Game, message);
+        }
+
+        assert_eq!(messages(&path), ["fourth"]);
+        assert_eq!(messages(&sink.rotated_path(1)), ["third"]);
+        assert_eq!(messages(&sink.rotated_path(2)), ["second"]);
+        assert!(!sink.rotated_path(3).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_sink_counts_what_the_file_already_holds() {
+        let dir = log_dir("append");
+        let path = dir.join("game.log");
+        fs::write(&path, "x".repeat(50)).unwrap();
+        let mut sink = RotatingFileSink::new(&path, 60, 1).unwrap();
+        sink.write(Level::Warn, Category::Audio, "after restart");
+        assert_eq!(messages(&path), ["after restart"]);
+        assert_eq!(fs::read_to_string(sink.rotated_path(1)).unwrap().len(), 50);
+
+        // A line longer than the limit still goes into an empty file whole
+        let long = "y".repeat(100);
+        let mut sink = RotatingFileSink::new(&dir.join("long.log"), 60, 1).unwrap();
+        sink.write(Level::Info, Category::Game, &long);
+        assert_eq!(messages(&dir.join("long.log")), [long]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_sink_keeping_no_files_starts_over() {
+        let dir = log_dir("truncate");
+        let path = dir.join("game.log");
+        let mut sink = RotatingFileSink::new(&path, 60, 0).unwrap();
+        sink.write(Level::Info, Category::Game, "first");
+        sink.write(Level::Info, Category::Game, "second");
+        assert_eq!(messages(&path), ["second"]);
+        assert!(!sink.rotated_path(1).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,54 +1,396 @@
-use deckbuilder::{tutorial::TutorialState, CoreGameState};
+use deckbuilder::{
+    actions::GameAction,
+    cards::CardDatabase,
+    enemies::EnemyDatabase,
+    hints::Suggestion,
+    log_export::LogFormat,
+    logger::{LogCategory, LogCursor, LogFilter},
+    logging::{self, LogLevels},
+    render::{render_intent, render_statuses},
+    replay::{Replay, ReplayPlayback},
+    run::{RunOutcome, RunPhase, RunState},
+    synth::{self, Sound, SAMPLE_RATE},
+    tutorial::TutorialState,
+    CoreGameState, Player, DEFAULT_ENEMY,
+};
 use std::io;
+use std::path::{Path, PathBuf};
 
-fn display_full_log(game: &CoreGameState) {
-    let log = game.get_log();
-    if log.is_empty() {
-        println!("No game log entries.");
+/// Prints the log entries matching `filter`: a turn number, a category
+/// name, or nothing for the whole log.
+fn display_log(game: &CoreGameState, filter: &str) {
+    let filter = if filter.is_empty() {
+        LogFilter::default()
+    } else if let Ok(turn) = filter.parse() {
+        LogFilter::turn(turn)
+    } else if let Some(category) = LogCategory::from_name(filter) {
+        LogFilter::categories(&[category])
     } else {
-        println!("Full game log:");
-        for entry in log.iter() {
-            println!("  {}", entry);
+        let names = LogCategory::ALL.map(LogCategory::name);
+        println!(
+            "Filter the log by a turn number or one of: {}",
+            names.join(", ")
+        );
+        return;
+    };
+    let entries = game.query_log(&filter).collect::<Vec<_>>();
+    if entries.is_empty() {
+        println!("No game log entries.");
+        return;
+    }
+    for entry in entries {
+        println!("  Turn {}: {}", entry.turn, entry.text());
+    }
+}
+
+/// Prints the game log entries added since `cursor`. Game text goes straight
+/// to stdout rather than through `logging`, so `DECKBUILDER_LOG` can't hide
+/// the game.
+fn print_new_entries(game: &CoreGameState, cursor: &mut LogCursor) {
+    for entry in game.log_since(cursor) {
+        println!("{}", entry.text());
+    }
+}
+
+/// Writes the whole log to `path`, in the format its extension asks for.
+fn export_log(game: &CoreGameState, path: &str) {
+    let format = LogFormat::for_file(path);
+    let contents = game.export_log(format, &LogFilter::default());
+    match std::fs::write(path, contents) {
+        Ok(()) => println!("Log exported to {} as {}.", path, format.name()),
+        Err(err) => println!("Failed to export the log: {}", err),
+    }
+}
+
+#[derive(Default)]
+struct CliOptions {
+    seed: Option<u64>,
+    cards: Option<PathBuf>,
+    enemies: Option<PathBuf>,
+    enemy: Option<String>,
+    run: bool,
+    load: Option<PathBuf>,
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    step: bool,
+    sound: Option<String>,
+    wav: Option<PathBuf>,
+}
+
+fn parse_args() -> CliOptions {
+    let mut options = CliOptions::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => options.seed = args.next().and_then(|seed| seed.parse().ok()),
+            "--cards" => options.cards = args.next().map(PathBuf::from),
+            "--enemies" => options.enemies = args.next().map(PathBuf::from),
+            "--enemy" => options.enemy = args.next(),
+            "--run" => options.run = true,
+            "--load" => options.load = args.next().map(PathBuf::from),
+            "--record" => options.record = args.next().map(PathBuf::from),
+            "--replay" => options.replay = args.next().map(PathBuf::from),
+            "--step" => options.step = true,
+            "--sound" => options.sound = args.next(),
+            "--wav" => options.wav = args.next().map(PathBuf::from),
+            _ => eprintln!("Ignoring unknown argument: {}", arg),
         }
     }
-    println!();
+    options
+}
+
+fn load_cards(cards: Option<&PathBuf>) -> CardDatabase {
+    let Some(path) = cards else {
+        return CardDatabase::builtin();
+    };
+    let database = CardDatabase::load(path).unwrap_or_else(|err| {
+        eprintln!("Failed to load cards: {}", err);
+        std::process::exit(1);
+    });
+    if database.starter_deck().is_empty() {
+        eprintln!(
+            "Failed to load cards: no card in {} has starter_copies set",
+            path.display()
+        );
+        std::process::exit(1);
+    }
+    database
+}
+
+fn load_enemies(enemies: Option<&PathBuf>) -> EnemyDatabase {
+    let Some(path) = enemies else {
+        return EnemyDatabase::builtin();
+    };
+    EnemyDatabase::load(path).unwrap_or_else(|err| {
+        eprintln!("Failed to load enemies: {}", err);
+        std::process::exit(1);
+    })
+}
+
+/// Where 's' saves to when no file name is given.
+const DEFAULT_SAVE_FILE: &str = "savegame.ron";
+
+/// Renders a named sound effect to a WAV file, for `--sound`.
+fn export_sound(name: &str, path: Option<&PathBuf>) {
+    let Some(sound) = Sound::from_name(name) else {
+        let names = Sound::ALL.map(Sound::name);
+        eprintln!(
+            "Unknown sound \"{}\". Available sounds: {}",
+            name,
+            names.join(", ")
+        );
+        std::process::exit(1);
+    };
+    let path = path
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.wav", name)));
+    let samples = synth::render(sound.preset(), SAMPLE_RATE);
+    if let Err(err) = synth::write_wav(&path, &samples, SAMPLE_RATE as u32) {
+        eprintln!("Failed to write {}: {}", path.display(), err);
+        std::process::exit(1);
+    }
+    println!(
+        "Wrote {} ({:.2}s, {} samples)",
+        path.display(),
+        samples.len() as f32 / SAMPLE_RATE,
+        samples.len()
+    );
 }
+
 fn main() {
-    let mut game = CoreGameState::new();
+    logging::configure_from_env(LogLevels::default());
+    let options = parse_args();
+    if let Some(name) = &options.sound {
+        export_sound(name, options.wav.as_ref());
+        return;
+    }
+    if let Some(path) = &options.load {
+        let mut game = CoreGameState::load(path).unwrap_or_else(|err| {
+            eprintln!("Failed to load {}: {}", path.display(), err);
+            std::process::exit(1);
+        });
+        println!("Loaded game from {}", path.display());
+        let cursor = game.log_cursor();
+        play_combat(&mut game, cursor, true);
+        record_replay(&game, options.record.as_ref());
+        return;
+    }
+    let cards = load_cards(options.cards.as_ref());
+    let enemies = load_enemies(options.enemies.as_ref());
+    if let Some(path) = &options.replay {
+        play_replay(path, &cards, &enemies, options.step);
+        return;
+    }
+    let seed = options.seed.unwrap_or_else(rand::random);
+    if options.run {
+        match RunState::new(seed, cards, enemies) {
+            Ok(run) => play_run(run),
+            Err(err) => {
+                eprintln!("Failed to start a run: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    let enemy_name = options.enemy.as_deref().unwrap_or(DEFAULT_ENEMY);
+    let enemy = match options.enemy {
+        Some(_) => enemies.get(enemy_name),
+        // Custom enemy files don't have to include the default enemy
+        None => enemies.get(DEFAULT_ENEMY).or(enemies.enemies().first()),
+    };
+    let Some(enemy) = enemy else {
+        let names = enemies
+            .enemies()
+            .iter()
+            .map(|enemy| enemy.name.as_str())
+            .collect::<Vec<_>>();
+        eprintln!(
+            "Unknown enemy \"{}\". Available enemies: {}",
+            enemy_name,
+            names.join(", ")
+        );
+        std::process::exit(1);
+    };
+    let player = Player::with_deck(cards.starter_deck());
+    let mut game = CoreGameState::with_enemy(seed, player, enemy);
+
+    println!(
+        "Game seed: {} (rerun with --seed {} to replay)",
+        game.seed(),
+        game.seed()
+    );
 
     println!(
         "Enemy: Health = {}, Attack = {}",
         game.enemy.health, game.enemy.attack
     );
 
-    // Main game loop
-    loop {
-        // Increment turn counter
-        game.increment_turn();
+    play_combat(&mut game, LogCursor::default(), true);
+    record_replay(&game, options.record.as_ref());
+}
 
-        println!("Turn {}", game.get_turn_count());
+/// Writes the game as a replay if `--record` was given.
+fn record_replay(game: &CoreGameState, path: Option<&PathBuf>) {
+    let Some(path) = path else {
+        return;
+    };
+    match Replay::from_game(game).save(path) {
+        Ok(()) => println!("Replay saved. Watch it with --replay {}", path.display()),
+        Err(err) => eprintln!("Failed to save the replay: {}", err),
+    }
+}
+
+/// Plays a replay back, a step at a time if `step` is set, and checks that
+/// it ends in the recorded state. Exits with an error if it doesn't.
+fn play_replay(path: &Path, cards: &CardDatabase, enemies: &EnemyDatabase, step: bool) {
+    let replay = Replay::load(path).unwrap_or_else(|err| {
+        eprintln!("Failed to load {}: {}", path.display(), err);
+        std::process::exit(1);
+    });
+    let player = Player::with_deck(cards.starter_deck());
+    let mut playback = ReplayPlayback::new(replay, player, enemies).unwrap_or_else(|err| {
+        eprintln!("Failed to start the replay: {}", err);
+        std::process::exit(1);
+    });
 
-        // Handle turn-based events
-        game.handle_turn_events();
+    let mut cursor = LogCursor::default();
+    print_new_entries(playback.game(), &mut cursor);
+    if step {
+        while let Some(action) = playback.next_action() {
+            println!(
+                "\n[Replay {}/{}] Next action: {:?}. Press Enter to play it, or 'a' to play the rest.",
+                playback.position() + 1,
+                playback.replay().actions.len(),
+                action
+            );
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("Failed to read line");
+            if input.trim() == "a" {
+                break;
+            }
+            if let Err(err) = playback.step() {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            print_new_entries(playback.game(), &mut cursor);
+        }
+    }
 
-        // Refresh player's mana at the start of each turn
-        game.player.restore_mana();
+    let result = playback.run_to_end();
+    print_new_entries(playback.game(), &mut cursor);
+    match result {
+        Ok(()) => println!(
+            "Replay verified: {} actions, final state hash {:016x}",
+            playback.replay().actions.len(),
+            playback.game().state_hash()
+        ),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
 
-        // Check if hand is empty and draw cards if necessary
-        if game.player.hand.is_empty() {
-            println!("Your hand is empty. Drawing new cards...");
-            for _ in 0..5 {
-                if let Some(card) = game.draw_card() {
+fn play_run(mut run: RunState) {
+    println!(
+        "Run seed: {} (rerun with --run --seed {} to replay)",
+        run.seed(),
+        run.seed()
+    );
+    let route = run
+        .map()
+        .iter()
+        .map(|encounter| encounter.enemy.as_str())
+        .collect::<Vec<_>>();
+    println!("The path up the mountain: {}", route.join(" -> "));
+
+    loop {
+        match run.phase() {
+            RunPhase::Combat => {
+                println!(
+                    "\n=== Fight {}/{}: {} ===",
+                    run.floor() + 1,
+                    run.map().len(),
+                    run.combat().enemy.name
+                );
+                if !play_combat(run.combat_mut(), LogCursor::default(), false) {
+                    return;
+                }
+                run.finish_combat()
+                    .expect("play_combat only returns true once the fight is over");
+            }
+            RunPhase::Reward => {
+                println!("\nVictory! Health: {}", run.health());
+                println!("Choose a card to add to your deck:");
+                for (i, card) in run.reward_choices().iter().enumerate() {
                     println!(
-                        "Drew: {} (Attack: {}, Defense: {}, Mana Cost: {})",
-                        card.name, card.attack, card.defense, card.mana_cost
+                        "{}. {} (Attack: {}, Defense: {}, Mana Cost: {})",
+                        i + 1,
+                        card.name,
+                        card.attack,
+                        card.defense,
+                        card.mana_cost
                     );
-                } else {
-                    println!("No more cards to draw!");
-                    break;
                 }
+                println!("Enter a number, or 's' to skip the reward:");
+                let mut input = String::new();
+                io::stdin()
+                    .read_line(&mut input)
+                    .expect("Failed to read line");
+                let choice = match input.trim() {
+                    "s" => None,
+                    input => match input.parse::<usize>() {
+                        Ok(index) if index > 0 => Some(index - 1),
+                        _ => {
+                            println!("Invalid choice. Please try again.");
+                            continue;
+                        }
+                    },
+                };
+                if let Err(err) = run.choose_reward(choice) {
+                    println!("{}. Please try again.", err);
+                }
+            }
+            RunPhase::Over(outcome) => {
+                println!("\n==================================================");
+                println!(
+                    "{}",
+                    match outcome {
+                        RunOutcome::Victory => "RUN COMPLETE: You have conquered the mountain!",
+                        RunOutcome::Defeat => "RUN OVER: The mountain claims another challenger.",
+                        RunOutcome::Abandoned =>
+                            "RUN OVER: You turn back and head down the mountain.",
+                    }
+                );
+                println!(
+                    "Fights won: {}/{}",
+                    run.floor() + usize::from(outcome == RunOutcome::Victory),
+                    run.map().len()
+                );
+                println!("Final health: {}", run.health());
+                println!("Final deck ({} cards):", run.deck().len());
+                for card in run.deck() {
+                    println!("  {}", card.name);
+                }
+                println!("==================================================");
+                return;
             }
         }
+    }
+}
+
+/// Plays one fight from the terminal until it ends or the player quits,
+/// printing the game log from `cursor` on. Returns false if the player quit.
+/// The tutorial and saving are only offered for `standalone` fights, not
+/// ones that are part of a run.
+fn play_combat(game: &mut CoreGameState, mut cursor: LogCursor, standalone: bool) -> bool {
+    loop {
+        // Setup, comments and redone actions
+        print_new_entries(game, &mut cursor);
+        // The core has already run the start of the turn and any draws
+        println!("Turn {}", game.get_turn_count());
 
         // Display player's hand and mana
         println!("Your hand:");
@@ -63,13 +405,27 @@ fn main() {
             );
         }
         println!(
-            "Current Mana: {}/{}",
-            game.player.current_mana, game.player.max_mana
+            "Current Mana: {}/{}  Statuses: {}",
+            game.player.current_mana,
+            game.player.max_mana,
+            render_statuses(&game.player.statuses)
+        );
+        println!(
+            "Enemy Health: {}  Statuses: {}",
+            game.enemy.health,
+            render_statuses(&game.enemy.statuses)
         );
+        if let Some(intent) = game.enemy_intent() {
+            println!(
+                "{} intends to {}.",
+                game.enemy.name,
+                render_intent(intent, game.incoming_damage())
+            );
+        }
 
         // Player's turn
         println!(
-            "Enter the number of the card you want to play, 'c' to add a comment, 't' for tutorial, or 'q' to quit:"
+            "Enter the number of a card to play, 'e' to end your turn, 'u' to undo, 'r' to redo, 'c' to add a comment, 'h' for a hint, 'l' to show the log (add a turn or category to filter it), 'x' to export the log, 's' to save, 't' for tutorial, or 'q' to quit:"
         );
         let mut input = String::new();
         io::stdin()
@@ -79,19 +435,90 @@ fn main() {
         let input = input.trim();
 
         match input {
-            "q" => break,
+            "q" => return false,
+            "u" => {
+                match game.undo() {
+                    Ok(()) => println!("Undid your last action."),
+                    Err(err) => println!("{}.", err),
+                }
+                continue;
+            }
+            "r" => {
+                match game.redo() {
+                    Ok(()) => println!("Redid your last undone action."),
+                    Err(err) => println!("{}.", err),
+                }
+                continue;
+            }
             "c" => {
                 println!("Enter your comment:");
                 let mut comment = String::new();
                 io::stdin()
                     .read_line(&mut comment)
                     .expect("Failed to read comment");
-                game.add_user_comment(comment.trim().to_string());
+                game.apply(GameAction::Comment(comment.trim().to_string()))
+                    .expect("Comments are always accepted");
                 println!("Comment added to the log.");
                 continue;
             }
+            "h" => {
+                match game.suggest_play() {
+                    Some(Suggestion {
+                        card: Some(index),
+                        reason,
+                    }) => println!(
+                        "Hint: play {}. {} ({}).",
+                        index + 1,
+                        game.get_hand()[index].name,
+                        reason
+                    ),
+                    Some(Suggestion { card: None, reason }) => {
+                        println!("Hint: end your turn ({}).", reason)
+                    }
+                    None => println!("There is nothing to suggest right now."),
+                }
+                continue;
+            }
+            "x" => {
+                println!("Enter a file to export to (.md for a match report, .jsonl for JSON lines, anything else for text):");
+                let mut path = String::new();
+                io::stdin()
+                    .read_line(&mut path)
+                    .expect("Failed to read file name");
+                match path.trim() {
+                    "" => println!("No file given; nothing exported."),
+                    path => export_log(game, path),
+                }
+                continue;
+            }
+            _ if input == "l" || input.starts_with("l ") => {
+                display_log(game, input[1..].trim());
+                continue;
+            }
+            "s" | "t" if !standalone => {
+                println!("That isn't available during a run.");
+                continue;
+            }
+            "s" => {
+                println!("Enter a file to save to (default: {}):", DEFAULT_SAVE_FILE);
+                let mut path = String::new();
+                io::stdin()
+                    .read_line(&mut path)
+                    .expect("Failed to read file name");
+                let path = match path.trim() {
+                    "" => DEFAULT_SAVE_FILE,
+                    path => path,
+                };
+                match game.save(Path::new(path)) {
+                    Ok(()) => println!("Game saved. Resume it with --load {}", path),
+                    Err(err) => println!("Failed to save: {}", err),
+                }
+                continue;
+            }
             "t" => {
                 let mut tutorial = TutorialState::new();
+                let mut tutorial_cursor = LogCursor::default();
+                print_new_entries(&tutorial.core_game, &mut tutorial_cursor);
                 loop {
                     let instruction = tutorial.get_current_instruction();
                     println!("{}", instruction);
@@ -100,6 +527,7 @@ fn main() {
                         .read_line(&mut tutorial_input)
                         .expect("Failed to read tutorial input");
                     let response = tutorial.handle_input(tutorial_input.trim());
+                    print_new_entries(&tutorial.core_game, &mut tutorial_cursor);
                     if !response.is_empty() {
                         println!("{}", response);
                     }
@@ -108,7 +536,8 @@ fn main() {
                     }
                 }
                 // Update the game state with the tutorial's core game
-                game = tutorial.core_game;
+                *game = tutorial.core_game;
+                cursor = game.log_cursor();
                 println!("Tutorial completed. Returning to the main game.");
                 println!(
                     "Enemy: Health = {}, Attack = {}",
@@ -116,40 +545,44 @@ fn main() {
                 );
                 continue;
             }
+            "e" => {
+                // Enemy's turn and the start of the next one
+                if let Err(err) = game.apply(GameAction::EndTurn) {
+                    println!("{}", err);
+                }
+                print_new_entries(game, &mut cursor);
+                println!("Player Health: {}", game.get_player_health());
+            }
             _ => {
                 // Process player's move
                 if let Ok(index) = input.parse::<usize>() {
                     if index > 0 && index <= game.get_hand().len() {
-                        let result = game.play_card(index as i32 - 1);
-                        println!("{}", result);
+                        if let Err(err) = game.apply(GameAction::PlayCard(index - 1)) {
+                            println!("{}. Please try again.", err);
+                            continue;
+                        }
+                        print_new_entries(game, &mut cursor);
                     } else {
                         println!("Invalid card number. Please try again.");
                         continue;
                     }
                 } else {
                     println!(
-                        "Invalid input. Please enter a number, 'c' to comment, 't' for tutorial, or 'q' to quit."
+                        "Invalid input. Please enter a number, 'e' to end your turn, 'u' to undo, 'r' to redo, 'c' to comment, 'h' for a hint, 'l' to show the log (add a turn or category to filter it), 'x' to export the log, 's' to save, 't' for tutorial, or 'q' to quit."
                     );
                     continue;
                 }
+
+                // Display updated status
+                println!("Player Health: {}", game.get_player_health());
+                println!("Enemy Health: {}", game.get_enemy_health());
             }
         }
 
-        // Display updated status
-        println!("Player Health: {}", game.get_player_health());
-        println!("Enemy Health: {}", game.get_enemy_health());
-
-        // Enemy's turn
-        game.enemy_turn();
-        println!("Player Health: {}", game.get_player_health());
-
-        // Display full game log
-        display_full_log(&game);
-
-        // Check for win/lose conditions
-        if let Some(game_over_message) = game.check_game_over() {
-            println!("{}", game_over_message);
-            break;
+        // Check for win/lose conditions; the game has already logged the
+        // outcome
+        if game.outcome().is_some() {
+            return true;
         }
     }
 }
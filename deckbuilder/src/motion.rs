@@ -0,0 +1,285 @@
+//! 2D kinematics for things that bounce around inside a rectangle, like
+//! `MyPlayer`. Nothing here depends on Godot; `MyPlayer` copies its node's
+//! position in, steps the body and copies the result back out.
+
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2::new(0.0, 0.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Vec2 { x, y }
+    }
+
+    pub fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// The vector scaled to length 1, or zero if it has no length.
+    pub fn normalized(self) -> Vec2 {
+        let length = self.length();
+        if length == 0.0 {
+            Vec2::ZERO
+        } else {
+            self * (1.0 / length)
+        }
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, scale: f32) -> Vec2 {
+        Vec2::new(self.x * scale, self.y * scale)
+    }
+}
+
+/// An axis-aligned rectangle from `min` (top left) to `max` (bottom right),
+/// with y pointing down as in Godot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Aabb { min, max }
+    }
+
+    pub fn from_position_size(position: Vec2, size: Vec2) -> Self {
+        Aabb::new(position, position + size)
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Aabb {
+        Aabb::new(self.min + offset, self.max + offset)
+    }
+}
+
+/// An edge of the area a body moves in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wall {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl fmt::Display for Wall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Wall::Left => "left",
+            Wall::Right => "right",
+            Wall::Top => "top",
+            Wall::Bottom => "bottom",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A body bounced off a wall during a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounce {
+    pub wall: Wall,
+    /// The body's velocity after the bounce.
+    pub velocity: Vec2,
+}
+
+/// A rectangle moving at a constant velocity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Body {
+    pub position: Vec2,
+    /// In units per second.
+    pub velocity: Vec2,
+    /// The body's extent relative to `position`. The position doesn't have
+    /// to be a corner: `MyPlayer`'s rectangle is centered on its origin.
+    pub shape: Aabb,
+}
+
+impl Body {
+    pub fn new(position: Vec2, velocity: Vec2, shape: Aabb) -> Self {
+        Body {
+            position,
+            velocity,
+            shape,
+        }
+    }
+
+    /// Where the body's rectangle is now.
+    pub fn bounds(&self) -> Aabb {
+        self.shape.translated(self.position)
+    }
+
+    /// Moves the body `delta` seconds, keeping it inside `area`. Hitting a
+    /// wall reflects the body's velocity and whatever distance it would have
+    /// travelled past the wall. Hitting a corner bounces off both walls, so
+    /// it reports two bounces.
+    ///
+    /// A body outside `area` (say, after the area shrank) is pulled back in,
+    /// bouncing only if it was heading further out. On an axis where the
+    /// area is smaller than the body, the body is centered and stops
+    /// bouncing until there is room again.
+    pub fn step(&mut self, delta: f32, area: Aabb) -> Vec<Bounce> {
+        let mut walls = Vec::new();
+        let x = step_axis(
+            &mut self.position.x,
+            &mut self.velocity.x,
+            delta,
+            (area.min.x - self.shape.min.x, area.max.x - self.shape.max.x),
+        );
+        if let Some(side) = x {
+            walls.push(side.wall(Wall::Left, Wall::Right));
+        }
+        let y = step_axis(
+            &mut self.position.y,
+            &mut self.velocity.y,
+            delta,
+            (area.min.y - self.shape.min.y, area.max.y - self.shape.max.y),
+        );
+        if let Some(side) = y {
+            walls.push(side.wall(Wall::Top, Wall::Bottom));
+        }
+        let velocity = self.velocity;
+        walls
+            .into_iter()
+            .map(|wall| Bounce { wall, velocity })
+            .collect()
+    }
+}
+
+enum Side {
+    Low,
+    High,
+}
+
+impl Side {
+    fn wall(self, low: Wall, high: Wall) -> Wall {
+        match self {
+            Side::Low => low,
+            Side::High => high,
+        }
+    }
+}
+
+/// Moves along one axis, keeping `position` within `(low, high)`.
+fn step_axis(
+    position: &mut f32,
+    velocity: &mut f32,
+    delta: f32,
+    range: (f32, f32),
+) -> Option<Side> {
+    let (low, high) = range;
+    if low > high {
+        *position = (low + high) / 2.0;
+        return None;
+    }
+    *position += *velocity * delta;
+    if *position < low {
+        if *velocity < 0.0 {
+            *velocity = -*velocity;
+            *position = (2.0 * low - *position).min(high);
+            return Some(Side::Low);
+        }
+        *position = low;
+    } else if *position > high {
+        if *velocity > 0.0 {
+            *velocity = -*velocity;
+            *position = (2.0 * high - *position).max(low);
+            return Some(Side::High);
+        }
+        *position = high;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 20x20 body centered on its position, like `MyPlayer`'s rectangle.
+    fn body(position: Vec2, velocity: Vec2) -> Body {
+        let shape = Aabb::from_position_size(Vec2::new(-10.0, -10.0), Vec2::new(20.0, 20.0));
+        Body::new(position, velocity, shape)
+    }
+
+    fn area(width: f32, height: f32) -> Aabb {
+        Aabb::new(Vec2::ZERO, Vec2::new(width, height))
+    }
+
+    fn assert_near(found: Vec2, expected: Vec2) {
+        assert!(
+            (found - expected).length() < 1e-3,
+            "{:?} is not {:?}",
+            found,
+            expected
+        );
+    }
+
+    #[test]
+    fn corner_hit_bounces_off_both_walls() {
+        let mut body = body(Vec2::new(15.0, 15.0), Vec2::new(-100.0, -100.0));
+        let bounces = body.step(0.1, area(100.0, 100.0));
+        let walls = bounces.iter().map(|bounce| bounce.wall).collect::<Vec<_>>();
+        assert_eq!(walls, [Wall::Left, Wall::Top]);
+        assert_near(body.velocity, Vec2::new(100.0, 100.0));
+    }
+
+    #[test]
+    fn overshoot_is_reflected_back_inside() {
+        // 5 units past the right edge's limit of 90 ends up 5 units inside it
+        let mut body = body(Vec2::new(85.0, 50.0), Vec2::new(100.0, 0.0));
+        let bounces = body.step(0.1, area(100.0, 100.0));
+        assert_eq!(bounces.len(), 1);
+        assert_eq!(bounces[0].wall, Wall::Right);
+        assert_near(body.position, Vec2::new(85.0, 50.0));
+        assert_near(body.velocity, Vec2::new(-100.0, 0.0));
+    }
+
+    #[test]
+    fn area_smaller_than_body_centers_it() {
+        let mut body = body(Vec2::new(3.0, 8.0), Vec2::new(100.0, -100.0));
+        let bounces = body.step(0.1, area(10.0, 10.0));
+        assert!(bounces.is_empty());
+        assert_near(body.position, Vec2::new(5.0, 5.0));
+    }
+
+    #[test]
+    fn body_outside_shrunken_area_is_pulled_back_in() {
+        // Heading back in: clamped to the edge, no bounce
+        let mut inward = body(Vec2::new(200.0, 50.0), Vec2::new(-100.0, 0.0));
+        assert!(inward.step(0.1, area(100.0, 100.0)).is_empty());
+        assert_near(inward.position, Vec2::new(90.0, 50.0));
+        assert_near(inward.velocity, Vec2::new(-100.0, 0.0));
+
+        // Heading further out: bounces, and still ends up inside
+        let mut outward = body(Vec2::new(200.0, 50.0), Vec2::new(100.0, 0.0));
+        let bounces = outward.step(0.1, area(100.0, 100.0));
+        assert_eq!(bounces.len(), 1);
+        assert_eq!(bounces[0].wall, Wall::Right);
+        assert!((10.0..=90.0).contains(&outward.position.x));
+        assert_near(outward.velocity, Vec2::new(-100.0, 0.0));
+    }
+}
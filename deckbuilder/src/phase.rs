@@ -0,0 +1,65 @@
+use crate::events::{Combatant, DrawSource, GameEvent};
+use crate::{CoreGameState, HAND_SIZE};
+use serde::{Deserialize, Serialize};
+
+/// Where a game is within its turn cycle.
+///
+/// Only `PlayerMain` waits for player input; every other phase is run
+/// automatically by `CoreGameState::advance` and the game ends up either back
+/// in `PlayerMain` or in `GameOver`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnPhase {
+    StartOfTurn,
+    Draw,
+    PlayerMain,
+    EnemyTurn,
+    EndOfTurn,
+    GameOver,
+}
+
+impl CoreGameState {
+    pub fn phase(&self) -> TurnPhase {
+        self.phase
+    }
+
+    /// Runs phases until the player has a decision to make or the game ends.
+    pub(crate) fn advance(&mut self) {
+        loop {
+            if self.outcome().is_some() {
+                self.check_for_game_over();
+                self.phase = TurnPhase::GameOver;
+            }
+            match self.phase {
+                TurnPhase::StartOfTurn => {
+                    self.increment_turn();
+                    self.tick_statuses(Combatant::Player);
+                    self.handle_turn_events();
+                    self.player.restore_mana();
+                    self.choose_enemy_intent();
+                    self.phase = TurnPhase::Draw;
+                }
+                TurnPhase::Draw => {
+                    while self.player.hand.len() < HAND_SIZE {
+                        if self.draw_card_from(DrawSource::TurnStart).is_none() {
+                            break;
+                        }
+                    }
+                    self.phase = TurnPhase::PlayerMain;
+                }
+                TurnPhase::EnemyTurn => {
+                    self.enemy_turn();
+                    self.phase = TurnPhase::EndOfTurn;
+                }
+                TurnPhase::EndOfTurn => {
+                    let cards = self.player.hand.len();
+                    if cards > 0 {
+                        self.player.discard_pile.append(&mut self.player.hand);
+                        self.log(GameEvent::HandDiscarded { cards });
+                    }
+                    self.phase = TurnPhase::StartOfTurn;
+                }
+                TurnPhase::PlayerMain | TurnPhase::GameOver => return,
+            }
+        }
+    }
+}
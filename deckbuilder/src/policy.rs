@@ -0,0 +1,318 @@
+//! Automated players that pick actions for a `CoreGameState`, for
+//! simulations and autoplay.
+
+use crate::actions::GameAction;
+use crate::events::GameOutcome;
+use crate::logger::GameLogger;
+use crate::phase::TurnPhase;
+use crate::status::{StatusEffect, Statuses};
+use crate::undo::History;
+use crate::{Card, CoreGameState, SpecialAbility};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::cmp::Ordering;
+
+/// Picks the next action for a game waiting on the player. Policies only see
+/// the game; they should only return actions the game will accept.
+pub trait PlayerPolicy {
+    /// A short name for reports, e.g. "first-affordable".
+    fn name(&self) -> &str;
+
+    fn choose_action(&mut self, game: &CoreGameState) -> GameAction;
+}
+
+/// Plays the leftmost card it can afford until none are left, then ends the
+/// turn. A simple baseline to compare other policies against.
+#[derive(Debug, Default)]
+pub struct FirstAffordable;
+
+impl PlayerPolicy for FirstAffordable {
+    fn name(&self) -> &str {
+        "first-affordable"
+    }
+
+    fn choose_action(&mut self, game: &CoreGameState) -> GameAction {
+        game.get_hand()
+            .iter()
+            .position(|card| game.can_afford(card))
+            .map_or(GameAction::EndTurn, GameAction::PlayCard)
+    }
+}
+
+/// Picks uniformly among every legal action, ending the turn included.
+pub struct RandomPlay {
+    rng: ChaCha8Rng,
+}
+
+impl RandomPlay {
+    pub fn new(seed: u64) -> Self {
+        RandomPlay {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl PlayerPolicy for RandomPlay {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn choose_action(&mut self, game: &CoreGameState) -> GameAction {
+        game.legal_actions()
+            .choose(&mut self.rng)
+            .cloned()
+            .unwrap_or(GameAction::EndTurn)
+    }
+}
+
+/// Plays the affordable card with the most damage per mana, preferring more
+/// block between equals, and only ends the turn once nothing is affordable.
+#[derive(Debug, Default)]
+pub struct Greedy;
+
+impl PlayerPolicy for Greedy {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn choose_action(&mut self, game: &CoreGameState) -> GameAction {
+        let hand = game.get_hand();
+        let affordable = (0..hand.len()).filter(|&index| game.can_afford(&hand[index]));
+        // max_by keeps the last of equal cards, so compare in reverse to
+        // play the leftmost of them
+        affordable
+            .rev()
+            .max_by(|&a, &b| {
+                compare_damage_per_mana(game, &hand[a], &hand[b])
+                    .then(hand[a].defense.cmp(&hand[b].defense))
+            })
+            .map_or(GameAction::EndTurn, GameAction::PlayCard)
+    }
+}
+
+/// Compares damage per mana without dividing, so free cards rank above any
+/// card that costs mana and deals no more damage.
+fn compare_damage_per_mana(game: &CoreGameState, a: &Card, b: &Card) -> Ordering {
+    let (damage_a, damage_b) = (game.card_damage(a), game.card_damage(b));
+    match (a.mana_cost, b.mana_cost) {
+        (0, 0) => damage_a.cmp(&damage_b),
+        (0, _) if damage_a > 0 => Ordering::Greater,
+        (_, 0) if damage_b > 0 => Ordering::Less,
+        (cost_a, cost_b) => (damage_a * cost_b).cmp(&(damage_b * cost_a)),
+    }
+}
+
+/// Tries every legal action on copies of the game, finishes each copy's next
+/// few turns with `Greedy` several times over, and picks the action whose
+/// copies end up best on average. The copies get fresh randomness, so the
+/// policy never sees the real game's upcoming draws or events.
+pub struct Lookahead {
+    rng: ChaCha8Rng,
+    /// Playouts per candidate action.
+    pub rollouts: u32,
+    /// Turns each playout continues past the current one.
+    pub depth: u32,
+}
+
+impl Lookahead {
+    pub fn new(seed: u64) -> Self {
+        Lookahead {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            rollouts: 16,
+            depth: 2,
+        }
+    }
+
+    fn playout(&mut self, game: &CoreGameState, action: &GameAction) -> i64 {
+        let mut copy = game.rollout_copy(&mut self.rng);
+        let last_turn = copy.get_turn_count() + self.depth;
+        if copy.apply(action.clone()).is_err() {
+            return i64::MIN;
+        }
+        while copy.outcome().is_none() && copy.get_turn_count() <= last_turn {
+            let next = Greedy.choose_action(&copy);
+            if copy.apply(next).is_err() {
+                break;
+            }
+        }
+        evaluate(&copy)
+    }
+}
+
+impl PlayerPolicy for Lookahead {
+    fn name(&self) -> &str {
+        "lookahead"
+    }
+
+    fn choose_action(&mut self, game: &CoreGameState) -> GameAction {
+        let mut best = (i64::MIN, GameAction::EndTurn);
+        for action in game.legal_actions() {
+            let total = (0..self.rollouts)
+                .map(|_| self.playout(game, &action))
+                .fold(0i64, i64::saturating_add);
+            if total > best.0 {
+                best = (total, action);
+            }
+        }
+        best.1
+    }
+}
+
+/// How good a game looks for the player: winning sooner beats winning later,
+/// and otherwise the health gap between the player and the enemy decides.
+fn evaluate(game: &CoreGameState) -> i64 {
+    let health_gap = i64::from(game.player.health) - i64::from(game.enemy.health);
+    match game.outcome() {
+        Some(GameOutcome::Victory) => {
+            10_000 - 100 * i64::from(game.get_turn_count()) + i64::from(game.player.health)
+        }
+        Some(GameOutcome::Defeat) | Some(GameOutcome::Conceded) => -10_000 + health_gap,
+        None => health_gap,
+    }
+}
+
+impl CoreGameState {
+    /// Every action the game would accept now apart from comments and
+    /// conceding: each affordable card, then ending the turn. Empty once the
+    /// game is over.
+    pub fn legal_actions(&self) -> Vec<GameAction> {
+        if self.phase != TurnPhase::PlayerMain {
+            return Vec::new();
+        }
+        let mut actions = (0..self.player.hand.len())
+            .filter(|&index| self.can_afford(&self.player.hand[index]))
+            .map(GameAction::PlayCard)
+            .collect::<Vec<_>>();
+        actions.push(GameAction::EndTurn);
+        actions
+    }
+
+    pub fn can_afford(&self, card: &Card) -> bool {
+        card.mana_cost <= self.player.current_mana
+    }
+
+    /// Damage playing `card` would deal to the enemy right now, after
+    /// statuses and block.
+    pub fn card_damage(&self, card: &Card) -> u32 {
+        let mut enemy = self.enemy.statuses.clone();
+        let attack =
+            Statuses::resolve_attack(card.attack, &mut self.player.statuses.clone(), &mut enemy);
+        let avalanche = match card.special_ability {
+            Some(SpecialAbility::SummonAvalanche(damage)) => {
+                Statuses::attack_damage(damage, &Statuses::default(), &enemy)
+            }
+            _ => 0,
+        };
+        (attack + avalanche)
+            .saturating_sub(enemy.get(StatusEffect::Block))
+            .min(self.enemy.health)
+    }
+
+    /// A copy of the game for a policy to play ahead in. It has no log,
+    /// history or console output, and a new RNG seeded from `rng` with the
+    /// deck reshuffled, so it can't reveal what the real game will draw.
+    pub(crate) fn rollout_copy(&self, rng: &mut impl Rng) -> CoreGameState {
+        let mut copy = CoreGameState {
+            logger: GameLogger::silent(),
+            rng: ChaCha8Rng::seed_from_u64(rng.gen()),
+            actions: Vec::new(),
+            history: History::disabled(),
+            ..self.clone()
+        };
+        copy.player.deck.shuffle(&mut copy.rng);
+        copy
+    }
+}
+
+/// Every shipped policy's name, for command-line options.
+pub const POLICY_NAMES: &[&str] = &["first-affordable", "random", "greedy", "lookahead"];
+
+/// The shipped policy with this name. `seed` drives the policies that make
+/// random choices.
+pub fn policy_by_name(name: &str, seed: u64) -> Option<Box<dyn PlayerPolicy>> {
+    match name {
+        "first-affordable" => Some(Box::new(FirstAffordable)),
+        "random" => Some(Box::new(RandomPlay::new(seed))),
+        "greedy" => Some(Box::new(Greedy)),
+        "lookahead" => Some(Box::new(Lookahead::new(seed))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn card(name: &str, attack: u32, defense: u32, mana_cost: u32) -> Card {
+        Card {
+            name: name.to_string(),
+            attack,
+            defense,
+            lucky: false,
+            special_ability: None,
+            mana_cost,
+        }
+    }
+
+    /// A game whose hands mix cheap cards with ones costing the whole turn's
+    /// mana, so most hands hold cards the player can't afford.
+    fn game(seed: u64) -> CoreGameState {
+        let cards = [
+            card("Jab", 1, 0, 1),
+            card("Haymaker", 6, 0, 3),
+            card("Brace", 0, 2, 1),
+            card("Overreach", 9, 0, 4),
+        ];
+        let deck = cards.iter().cycle().take(16).cloned().collect();
+        CoreGameState::with_player(seed, Player::with_deck(deck))
+    }
+
+    /// Plays a game with `policy`, checking that every card it picks is
+    /// affordable, and returns the actions taken.
+    fn play(policy: &mut dyn PlayerPolicy, seed: u64, max_actions: usize) -> Vec<GameAction> {
+        let mut game = game(seed);
+        let mut actions = Vec::new();
+        while game.outcome().is_none() && actions.len() < max_actions {
+            let action = policy.choose_action(&game);
+            if let GameAction::PlayCard(index) = action {
+                let card = &game.get_hand()[index];
+                assert!(
+                    game.can_afford(card),
+                    "{} picked {} costing {} with {} mana",
+                    policy.name(),
+                    card.name,
+                    card.mana_cost,
+                    game.player.current_mana
+                );
+            }
+            game.apply(action.clone()).unwrap();
+            actions.push(action);
+        }
+        actions
+    }
+
+    #[test]
+    fn greedy_only_plays_affordable_cards() {
+        for seed in 0..5 {
+            play(&mut Greedy, seed, 200);
+        }
+    }
+
+    #[test]
+    fn lookahead_only_plays_affordable_cards() {
+        let mut lookahead = Lookahead::new(3);
+        lookahead.rollouts = 2;
+        play(&mut lookahead, 3, 40);
+    }
+
+    #[test]
+    fn random_play_follows_its_seed() {
+        let first = play(&mut RandomPlay::new(5), 1, 200);
+        let second = play(&mut RandomPlay::new(5), 1, 200);
+        assert_eq!(first, second);
+        let other_seed = play(&mut RandomPlay::new(6), 1, 200);
+        assert_ne!(first, other_seed);
+    }
+}
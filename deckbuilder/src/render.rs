@@ -0,0 +1,270 @@
+use crate::events::{Combatant, DrawSource, GameEvent, GameOutcome, TurnEvent};
+use crate::intents::EnemyIntent;
+use crate::status::{StatusEffect, Statuses};
+use crate::SpecialAbility;
+
+/// Produces the English console text for a single event.
+pub fn render_event(event: &GameEvent) -> String {
+    match event {
+        GameEvent::Narration(text) => text.clone(),
+        GameEvent::EnemyAppeared {
+            name,
+            health,
+            attack,
+        } => format!(
+            "Enemy stats: {} - Health = {}, Attack = {}",
+            name, health, attack
+        ),
+        GameEvent::TurnStarted { turn } => format!("Turn {} begins", turn),
+        GameEvent::TurnEventTriggered(turn_event) => render_turn_event(*turn_event).to_string(),
+        GameEvent::CardDrawn { card, source } => match source {
+            DrawSource::InitialHand => format!(
+                "- {}: A card infused with the power of {} (Attack: {}, Defense: {}, Mana Cost: {})",
+                card.name,
+                card.name.to_lowercase(),
+                card.attack,
+                card.defense,
+                card.mana_cost
+            ),
+            DrawSource::TurnStart => format!(
+                "Drew: {} (Attack: {}, Defense: {}, Mana Cost: {})",
+                card.name, card.attack, card.defense, card.mana_cost
+            ),
+            DrawSource::TurnEvent => format!("You draw an extra card: {}", card.name),
+            DrawSource::Ability => format!("Player drew: {}", card.name),
+        },
+        GameEvent::CardPlayed { card } => format!(
+            "Played card: {} (Mana cost: {}).",
+            card.name, card.mana_cost
+        ),
+        GameEvent::DamageDealt {
+            target: Combatant::Enemy,
+            amount,
+            health_before,
+            health_after,
+        } => format!(
+            "You dealt {} damage. Enemy health: {} -> {}.",
+            amount, health_before, health_after
+        ),
+        GameEvent::DamageDealt {
+            target: Combatant::Player,
+            amount,
+            health_before,
+            health_after,
+        } => format!(
+            "You take {} damage. Your health: {} -> {}.",
+            amount, health_before, health_after
+        ),
+        GameEvent::EnvironmentDamage {
+            target: Combatant::Enemy,
+            amount,
+            health_before,
+            health_after,
+        } => format!(
+            "The enemy takes {} damage from the battlefield. Enemy health: {} -> {}.",
+            amount, health_before, health_after
+        ),
+        GameEvent::EnvironmentDamage {
+            target: Combatant::Player,
+            amount,
+            health_before,
+            health_after,
+        } => format!(
+            "You take {} damage from the battlefield. Your health: {} -> {}.",
+            amount, health_before, health_after
+        ),
+        GameEvent::Healed {
+            target: Combatant::Enemy,
+            amount,
+            health_before,
+            health_after,
+        } => format!(
+            "The enemy recovers {} health. Enemy health: {} -> {}.",
+            amount, health_before, health_after
+        ),
+        GameEvent::Healed {
+            target: Combatant::Player,
+            amount,
+            health_before,
+            health_after,
+        } => format!(
+            "You recover {} health. Your health: {} -> {}.",
+            amount, health_before, health_after
+        ),
+        GameEvent::StatusApplied {
+            target: Combatant::Player,
+            effect: StatusEffect::Block,
+            stacks,
+            total,
+        } => format!("You raise {} block (total block: {}).", stacks, total),
+        GameEvent::StatusApplied {
+            target: Combatant::Player,
+            effect,
+            stacks,
+            total,
+        } => format!(
+            "You gain {} {} (total: {}).",
+            stacks,
+            render_status(*effect),
+            total
+        ),
+        GameEvent::StatusApplied {
+            target: Combatant::Enemy,
+            effect,
+            stacks,
+            total,
+        } => format!(
+            "Applied {} {} to the enemy (total: {}).",
+            stacks,
+            render_status(*effect),
+            total
+        ),
+        GameEvent::DamageBlocked {
+            target: Combatant::Player,
+            amount,
+        } => format!("Your block absorbs {} damage.", amount),
+        GameEvent::DamageBlocked {
+            target: Combatant::Enemy,
+            amount,
+        } => format!("The enemy's block absorbs {} damage.", amount),
+        GameEvent::HandDiscarded { cards } => {
+            format!("You discard the {} cards left in your hand.", cards)
+        }
+        GameEvent::MaxManaIncreased { amount } => {
+            format!("Your maximum mana increases by {}!", amount)
+        }
+        GameEvent::AvalancheSummoned { damage } => format!(
+            "You summon an avalanche, dealing {} damage to the enemy!",
+            damage
+        ),
+        GameEvent::PoisonTicked {
+            target: Combatant::Enemy,
+            amount,
+            health_after,
+        } => format!(
+            "The enemy took {} poison damage. Enemy health: {}",
+            amount, health_after
+        ),
+        GameEvent::PoisonTicked {
+            target: Combatant::Player,
+            amount,
+            health_after,
+        } => format!(
+            "Poison deals you {} damage. Your health: {}",
+            amount, health_after
+        ),
+        GameEvent::Stunned { turns } => format!("Stunned the enemy for {} turns", turns),
+        GameEvent::TurnSkipped { enemy } => format!("{} is stunned and cannot attack!", enemy),
+        GameEvent::EnemyPowerSurge { enemy, .. } => {
+            format!("{} surges with power, increasing its attack!", enemy)
+        }
+        GameEvent::IntentRevealed {
+            enemy,
+            intent,
+            damage,
+        } => format!("{} intends to {}.", enemy, render_intent(*intent, *damage)),
+        GameEvent::EnemyBuffed {
+            enemy,
+            attack,
+            total,
+        } => format!(
+            "{} grows stronger! Attack +{} (attack: {}).",
+            enemy, attack, total
+        ),
+        GameEvent::ManaDrained { enemy, mana } => format!(
+            "{} saps your strength. You will have {} less mana next turn.",
+            enemy, mana
+        ),
+        GameEvent::MinionsSummoned {
+            enemy,
+            count,
+            total,
+        } => format!(
+            "{} summons {} minion(s) to its side ({} total).",
+            enemy, count, total
+        ),
+        GameEvent::EnemyAttacked { enemy, .. } => format!("{} attacks!", enemy),
+        GameEvent::EnemyTaunt { enemy, verb, line } => {
+            format!("{} {}: \"{}\"", enemy, verb, line)
+        }
+        GameEvent::GameOver(GameOutcome::Defeat) => {
+            "Game Over: You have been defeated!".to_string()
+        }
+        GameEvent::GameOver(GameOutcome::Victory) => {
+            "Congratulations! You have defeated the enemy!".to_string()
+        }
+        GameEvent::GameOver(GameOutcome::Conceded) => {
+            "You retreat down the mountain. The battle is lost.".to_string()
+        }
+        GameEvent::UserComment(comment) => format!("User comment: {}", comment),
+    }
+}
+
+/// Describes an enemy intent, e.g. "attack for 3 damage". `damage` is the
+/// attack damage the intent will deal.
+pub fn render_intent(intent: EnemyIntent, damage: u32) -> String {
+    match intent {
+        EnemyIntent::Attack { .. } => format!("attack for {} damage", damage),
+        EnemyIntent::ShieldUp { amount } => format!("raise its shield for {} block", amount),
+        EnemyIntent::Buff { attack } => format!("increase its attack by {}", attack),
+        EnemyIntent::Debuff { mana } => format!("drain {} of your mana", mana),
+        EnemyIntent::Summon { minions } => format!("summon {} minion(s)", minions),
+        EnemyIntent::Inflict { effect, stacks } => {
+            format!("inflict {} {} on you", stacks, render_status(effect))
+        }
+        EnemyIntent::Gain { effect, stacks } => {
+            format!("gain {} {}", stacks, render_status(effect))
+        }
+    }
+}
+
+/// Describes what a card's special ability does, e.g. "Heal 3".
+pub fn render_ability(ability: &SpecialAbility) -> String {
+    match ability {
+        SpecialAbility::Heal(amount) => format!("Heal {}", amount),
+        SpecialAbility::DrawCards(amount) => format!("Draw {} card(s)", amount),
+        SpecialAbility::ApplyPoison(amount) => format!("Apply {} Poison", amount),
+        SpecialAbility::StunEnemy(turns) => format!("Stun for {} turn(s)", turns),
+        SpecialAbility::Inflict(effect, stacks) => {
+            format!("Inflict {} {}", stacks, render_status(*effect))
+        }
+        SpecialAbility::Gain(effect, stacks) => {
+            format!("Gain {} {}", stacks, render_status(*effect))
+        }
+        SpecialAbility::SummonAvalanche(damage) => {
+            format!("Summon an avalanche for {} damage", damage)
+        }
+    }
+}
+
+pub fn render_status(effect: StatusEffect) -> &'static str {
+    match effect {
+        StatusEffect::Block => "Block",
+        StatusEffect::Poison => "Poison",
+        StatusEffect::Vulnerable => "Vulnerable",
+        StatusEffect::Weak => "Weak",
+        StatusEffect::Strength => "Strength",
+    }
+}
+
+/// Lists active statuses like "Block 3, Poison 2", or "none".
+pub fn render_statuses(statuses: &Statuses) -> String {
+    let active = statuses
+        .active()
+        .map(|(effect, stacks)| format!("{} {}", render_status(effect), stacks))
+        .collect::<Vec<_>>();
+    if active.is_empty() {
+        "none".to_string()
+    } else {
+        active.join(", ")
+    }
+}
+
+fn render_turn_event(turn_event: TurnEvent) -> &'static str {
+    match turn_event {
+        TurnEvent::GustOfWind => "A sudden gust of wind sweeps across the battlefield!",
+        TurnEvent::GroundTremor => "The ground trembles beneath your feet!",
+        TurnEvent::MysteriousEnergy => "A mysterious energy fills the air...",
+        TurnEvent::AncientPower => "The mountain's ancient power surges through you!",
+    }
+}
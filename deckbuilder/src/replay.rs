@@ -0,0 +1,265 @@
+//! Recording games as replays (seed, enemy and the actions taken) and
+//! playing them back deterministically.
+
+use crate::actions::{ActionError, GameAction};
+use crate::enemies::EnemyDatabase;
+use crate::events::GameEvent;
+use crate::save::{self, SaveError};
+use crate::{CoreGameState, Player};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Bumped whenever a change to replays or to game rules makes older replays
+/// play out differently. Since the final state hash covers the whole game,
+/// including its log, a change to what gets logged counts too.
+///
+/// - 2: log entries gained a turn, time and category; ground tremor damage
+///   to the player is logged as environment damage; an enemy's shield is
+///   plain Block, which avalanches now hit.
+/// - 3: enemy health and attack rebalanced.
+/// - 4: both sides have a max health, and healing stops at it.
+pub const REPLAY_VERSION: u32 = 4;
+
+/// Everything needed to play a game again: the same seed, enemy and actions
+/// against the same card and enemy data reproduce it exactly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    version: u32,
+    pub seed: u64,
+    pub enemy: String,
+    pub actions: Vec<GameAction>,
+    /// `CoreGameState::state_hash` after the last action.
+    pub final_hash: u64,
+}
+
+/// Why a replay could not be played back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    File(SaveError),
+    UnknownEnemy(String),
+    /// The game rejected an action the recorded game accepted. `step` is
+    /// 0-based.
+    Rejected {
+        step: usize,
+        error: ActionError,
+    },
+    /// Every action was accepted, but the game ended up somewhere else.
+    HashMismatch {
+        expected: u64,
+        found: u64,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::File(err) => write!(f, "{}", err),
+            ReplayError::UnknownEnemy(name) => {
+                write!(f, "The replay is against unknown enemy \"{}\"", name)
+            }
+            ReplayError::Rejected { step, error } => write!(
+                f,
+                "Action {} of the replay was rejected: {}",
+                step + 1,
+                error
+            ),
+            ReplayError::HashMismatch { expected, found } => write!(
+                f,
+                "Replay diverged: final state hash is {:016x}, expected {:016x}",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<SaveError> for ReplayError {
+    fn from(err: SaveError) -> Self {
+        ReplayError::File(err)
+    }
+}
+
+impl Replay {
+    /// Records `game` as it stands now.
+    pub fn from_game(game: &CoreGameState) -> Self {
+        Replay {
+            version: REPLAY_VERSION,
+            seed: game.seed(),
+            enemy: game.enemy.name.clone(),
+            actions: game.actions().to_vec(),
+            final_hash: game.state_hash(),
+        }
+    }
+
+    pub fn to_replay_string(&self) -> Result<String, SaveError> {
+        // One action per line keeps replays short and easy to diff
+        save::encode(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn from_replay_str(source: &str) -> Result<Self, SaveError> {
+        save::decode(source, REPLAY_VERSION)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        save::write_file(path, &self.to_replay_string()?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SaveError> {
+        Self::from_replay_str(&save::read_file(path)?)
+    }
+}
+
+/// A replay being played back one action at a time.
+pub struct ReplayPlayback {
+    replay: Replay,
+    game: CoreGameState,
+    step: usize,
+}
+
+impl ReplayPlayback {
+    /// Starts the replayed game. `player` must start with the same deck as
+    /// the recorded game, i.e. come from the same card data.
+    pub fn new(
+        replay: Replay,
+        player: Player,
+        enemies: &EnemyDatabase,
+    ) -> Result<Self, ReplayError> {
+        let enemy = enemies
+            .get(&replay.enemy)
+            .ok_or_else(|| ReplayError::UnknownEnemy(replay.enemy.clone()))?;
+        let game = CoreGameState::with_enemy(replay.seed, player, enemy);
+        Ok(ReplayPlayback {
+            replay,
+            game,
+            step: 0,
+        })
+    }
+
+    pub fn game(&self) -> &CoreGameState {
+        &self.game
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// How many actions have been played back so far.
+    pub fn position(&self) -> usize {
+        self.step
+    }
+
+    pub fn next_action(&self) -> Option<&GameAction> {
+        self.replay.actions.get(self.step)
+    }
+
+    /// Plays the next action, returning its events, or `None` once every
+    /// action has been played.
+    pub fn step(&mut self) -> Result<Option<Vec<GameEvent>>, ReplayError> {
+        let Some(action) = self.next_action().cloned() else {
+            return Ok(None);
+        };
+        let events = self
+            .game
+            .apply(action)
+            .map_err(|error| ReplayError::Rejected {
+                step: self.step,
+                error,
+            })?;
+        self.step += 1;
+        Ok(Some(events))
+    }
+
+    /// Plays every remaining action and checks the final state.
+    pub fn run_to_end(&mut self) -> Result<(), ReplayError> {
+        while self.step()?.is_some() {}
+        self.verify()
+    }
+
+    /// Checks the game against the recorded final state hash. Only meaningful
+    /// once every action has been played.
+    pub fn verify(&self) -> Result<(), ReplayError> {
+        let found = self.game.state_hash();
+        if found == self.replay.final_hash {
+            Ok(())
+        } else {
+            Err(ReplayError::HashMismatch {
+                expected: self.replay.final_hash,
+                found,
+            })
+        }
+    }
+}
+
+impl CoreGameState {
+    /// Every action accepted by `apply` so far, in order.
+    pub fn actions(&self) -> &[GameAction] {
+        &self.actions
+    }
+
+    /// A fingerprint of the entire game state, stable across runs and
+    /// platforms, for checking that a replay reproduced a game exactly.
+    pub fn state_hash(&self) -> u64 {
+        // Log timestamps differ every time a game is played
+        let mut game = self.clone();
+        game.logger.clear_timestamps();
+        let state = ron::to_string(&game).expect("Game state always encodes as RON");
+        // FNV-1a, since std's hashers may change between Rust releases
+        state.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded_game() -> Replay {
+        let mut game = CoreGameState::with_seed(11);
+        for action in [
+            GameAction::PlayCard(0),
+            GameAction::EndTurn,
+            GameAction::Comment("second turn".to_string()),
+            GameAction::PlayCard(1),
+            GameAction::EndTurn,
+        ] {
+            let _ = game.apply(action);
+        }
+        Replay::from_game(&game)
+    }
+
+    fn play_back(replay: Replay) -> Result<(), ReplayError> {
+        ReplayPlayback::new(replay, Player::new(), &EnemyDatabase::builtin())?.run_to_end()
+    }
+
+    #[test]
+    fn recorded_game_replays_to_the_same_state() {
+        let replay = recorded_game();
+        let loaded = Replay::from_replay_str(&replay.to_replay_string().unwrap()).unwrap();
+        assert_eq!(loaded, replay);
+        assert_eq!(play_back(loaded), Ok(()));
+    }
+
+    #[test]
+    fn tampered_action_is_rejected() {
+        let mut replay = recorded_game();
+        replay.actions.insert(1, GameAction::PlayCard(99));
+        assert!(matches!(
+            play_back(replay),
+            Err(ReplayError::Rejected { step: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn changed_final_state_is_a_mismatch() {
+        let mut replay = recorded_game();
+        replay.final_hash ^= 1;
+        let expected = replay.final_hash;
+        assert!(matches!(
+            play_back(replay),
+            Err(ReplayError::HashMismatch { expected: hash, .. }) if hash == expected
+        ));
+    }
+}
@@ -0,0 +1,456 @@
+use crate::actions::{ActionError, GameAction};
+use crate::cards::CardDatabase;
+use crate::enemies::EnemyDatabase;
+use crate::events::{GameEvent, GameOutcome};
+use crate::{Card, CoreGameState, Player, STARTING_HEALTH};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+
+/// Regular fights on a generated map before the boss.
+pub const RUN_FIGHTS: usize = 3;
+/// Cards offered after each won fight.
+pub const REWARD_CHOICES: usize = 3;
+
+/// One fight on a run's map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encounter {
+    pub enemy: String,
+    pub boss: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// Every encounter on the map was won.
+    Victory,
+    /// The player ran out of health.
+    Defeat,
+    /// The player conceded a fight.
+    Abandoned,
+}
+
+/// Where a run is between and during its fights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunPhase {
+    /// A fight is in progress; actions go to `RunState::apply`.
+    Combat,
+    /// A fight was won and a card reward is waiting to be picked or skipped.
+    Reward,
+    Over(RunOutcome),
+}
+
+/// Why a `RunState` call was rejected. Nothing is changed when a call is
+/// rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    EmptyMap,
+    EmptyDeck,
+    UnknownEnemy(String),
+    /// The action was rejected by the current fight.
+    Action(ActionError),
+    WrongPhase {
+        phase: RunPhase,
+    },
+    InvalidReward {
+        index: usize,
+        choices: usize,
+    },
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::EmptyMap => write!(f, "A run needs at least one encounter"),
+            RunError::EmptyDeck => write!(f, "A run needs a starter deck with at least one card"),
+            RunError::UnknownEnemy(name) => write!(f, "Unknown enemy \"{}\" on the map", name),
+            RunError::Action(err) => write!(f, "{}", err),
+            RunError::WrongPhase { phase } => {
+                write!(
+                    f,
+                    "That can't be done while the run is in the {:?} phase",
+                    phase
+                )
+            }
+            RunError::InvalidReward { index, choices } => write!(
+                f,
+                "Invalid reward {} (there are {} to choose from)",
+                index, choices
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<ActionError> for RunError {
+    fn from(err: ActionError) -> Self {
+        RunError::Action(err)
+    }
+}
+
+/// A run: a series of fights on a map, with the player's deck and health
+/// carried from one `CoreGameState` to the next and a card reward after each
+/// win.
+pub struct RunState {
+    cards: CardDatabase,
+    enemies: EnemyDatabase,
+    map: Vec<Encounter>,
+    /// Index into `map` of the current (or last) fight.
+    floor: usize,
+    deck: Vec<Card>,
+    health: u32,
+    combat: CoreGameState,
+    rewards: Vec<Card>,
+    phase: RunPhase,
+    rng: ChaCha8Rng,
+    seed: u64,
+}
+
+impl RunState {
+    /// A run on a map generated from `enemies` with `generate_map`.
+    pub fn new(seed: u64, cards: CardDatabase, enemies: EnemyDatabase) -> Result<Self, RunError> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let map = generate_map(&enemies, &mut rng);
+        Self::with_map(seed, cards, enemies, map)
+    }
+
+    /// A run through the given encounters, starting the first fight.
+    pub fn with_map(
+        seed: u64,
+        cards: CardDatabase,
+        enemies: EnemyDatabase,
+        map: Vec<Encounter>,
+    ) -> Result<Self, RunError> {
+        if map.is_empty() {
+            return Err(RunError::EmptyMap);
+        }
+        if let Some(encounter) = map
+            .iter()
+            .find(|encounter| enemies.get(&encounter.enemy).is_none())
+        {
+            return Err(RunError::UnknownEnemy(encounter.enemy.clone()));
+        }
+        let deck = cards.starter_deck();
+        if deck.is_empty() {
+            return Err(RunError::EmptyDeck);
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        // Keep the map stream and the fight stream apart so a hand-made map
+        // plays out the same as an identical generated one.
+        rng.set_stream(1);
+        let combat = start_combat(&enemies, &map[0], deck.clone(), STARTING_HEALTH, &mut rng);
+        let mut run = RunState {
+            cards,
+            enemies,
+            map,
+            floor: 0,
+            deck,
+            health: STARTING_HEALTH,
+            combat,
+            rewards: Vec::new(),
+            phase: RunPhase::Combat,
+            rng,
+            seed,
+        };
+        run.finish_combat_if_over();
+        Ok(run)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn phase(&self) -> RunPhase {
+        self.phase
+    }
+
+    pub fn map(&self) -> &[Encounter] {
+        &self.map
+    }
+
+    /// Index into `map` of the current fight, or the last one once the run
+    /// is over.
+    pub fn floor(&self) -> usize {
+        self.floor
+    }
+
+    /// The deck the player starts each fight with.
+    pub fn deck(&self) -> &[Card] {
+        &self.deck
+    }
+
+    /// The player's health going into the next fight.
+    pub fn health(&self) -> u32 {
+        self.health
+    }
+
+    /// The current fight, or the one that just ended.
+    pub fn combat(&self) -> &CoreGameState {
+        &self.combat
+    }
+
+    /// Mutable access to the current fight, for frontends that drive it
+    /// directly. Call `finish_combat` once it has an outcome.
+    pub fn combat_mut(&mut self) -> &mut CoreGameState {
+        &mut self.combat
+    }
+
+    /// The cards offered for the fight just won.
+    pub fn reward_choices(&self) -> &[Card] {
+        &self.rewards
+    }
+
+    /// Applies an action to the current fight and moves the run on if the
+    /// fight ended.
+    pub fn apply(&mut self, action: GameAction) -> Result<Vec<GameEvent>, RunError> {
+        if self.phase != RunPhase::Combat && !matches!(action, GameAction::Comment(_)) {
+            return Err(RunError::WrongPhase { phase: self.phase });
+        }
+        let events = self.combat.apply(action)?;
+        if self.phase == RunPhase::Combat && self.combat.outcome().is_some() {
+            self.finish_combat()?;
+        }
+        Ok(events)
+    }
+
+    /// Ends the current fight once it has an outcome: a win offers card
+    /// rewards (or ends the run after the last encounter), anything else
+    /// ends the run.
+    pub fn finish_combat(&mut self) -> Result<RunPhase, RunError> {
+        let outcome = match (self.phase, self.combat.outcome()) {
+            (RunPhase::Combat, Some(outcome)) => outcome,
+            _ => return Err(RunError::WrongPhase { phase: self.phase }),
+        };
+        self.health = self.combat.player.health;
+        self.phase = match outcome {
+            GameOutcome::Victory if self.floor + 1 == self.map.len() => {
+                RunPhase::Over(RunOutcome::Victory)
+            }
+            GameOutcome::Victory => {
+                self.rewards = self.roll_rewards();
+                RunPhase::Reward
+            }
+            GameOutcome::Defeat => RunPhase::Over(RunOutcome::Defeat),
+            GameOutcome::Conceded => RunPhase::Over(RunOutcome::Abandoned),
+        };
+        Ok(self.phase)
+    }
+
+    /// Adds the chosen reward card to the deck (or skips the reward with
+    /// `None`) and starts the next fight.
+    pub fn choose_reward(&mut self, choice: Option<usize>) -> Result<(), RunError> {
+        if self.phase != RunPhase::Reward {
+            return Err(RunError::WrongPhase { phase: self.phase });
+        }
+        if let Some(index) = choice {
+            if index >= self.rewards.len() {
+                return Err(RunError::InvalidReward {
+                    index,
+                    choices: self.rewards.len(),
+                });
+            }
+            let card = self.rewards.swap_remove(index);
+            self.deck.push(card);
+        }
+        self.rewards.clear();
+        self.floor += 1;
+        self.combat = start_combat(
+            &self.enemies,
+            &self.map[self.floor],
+            self.deck.clone(),
+            self.health,
+            &mut self.rng,
+        );
+        self.phase = RunPhase::Combat;
+        self.finish_combat_if_over();
+        Ok(())
+    }
+
+    /// Battlefield events at the start of a fight can end it before the
+    /// player acts, so a new fight may need finishing straight away.
+    fn finish_combat_if_over(&mut self) {
+        if self.combat.outcome().is_some() {
+            self.finish_combat()
+                .expect("A fight with an outcome can be finished");
+        }
+    }
+
+    pub fn outcome(&self) -> Option<RunOutcome> {
+        match self.phase {
+            RunPhase::Over(outcome) => Some(outcome),
+            _ => None,
+        }
+    }
+
+    fn roll_rewards(&mut self) -> Vec<Card> {
+        self.cards
+            .cards()
+            .choose_multiple(&mut self.rng, REWARD_CHOICES)
+            .map(|definition| definition.to_card())
+            .collect()
+    }
+}
+
+/// A map of `RUN_FIGHTS` random regular enemies followed by a random boss.
+/// Without any bosses the last fight is another regular enemy, and without
+/// any regular enemies every fight is a boss.
+pub fn generate_map(enemies: &EnemyDatabase, rng: &mut impl Rng) -> Vec<Encounter> {
+    let (bosses, regulars): (Vec<_>, Vec<_>) =
+        enemies.enemies().iter().partition(|enemy| enemy.boss);
+    let regulars = if regulars.is_empty() {
+        &bosses
+    } else {
+        &regulars
+    };
+    let mut map = (0..RUN_FIGHTS)
+        .filter_map(|_| regulars.choose(rng))
+        .map(|enemy| Encounter {
+            enemy: enemy.name.clone(),
+            boss: enemy.boss,
+        })
+        .collect::<Vec<_>>();
+    let boss = bosses.choose(rng).or_else(|| regulars.choose(rng));
+    map.extend(boss.map(|enemy| Encounter {
+        enemy: enemy.name.clone(),
+        boss: true,
+    }));
+    map
+}
+
+fn start_combat(
+    enemies: &EnemyDatabase,
+    encounter: &Encounter,
+    mut deck: Vec<Card>,
+    health: u32,
+    rng: &mut ChaCha8Rng,
+) -> CoreGameState {
+    deck.shuffle(rng);
+    let player = Player {
+        health,
+        ..Player::with_deck(deck)
+    };
+    let enemy = enemies
+        .get(&encounter.enemy)
+        .expect("Map enemies are checked when the run is created");
+    CoreGameState::with_enemy(rng.gen(), player, enemy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enemies() -> EnemyDatabase {
+        let mut enemies = EnemyDatabase::default();
+        enemies
+            .add_source(
+                r#"[
+                    (name: "Pebble", health: 1, attack: 1),
+                    (name: "Boulder", health: 8, attack: 1),
+                    (name: "Titan", health: 500, attack: 20, boss: true),
+                ]"#,
+                "test_enemies.ron",
+            )
+            .unwrap();
+        enemies
+    }
+
+    fn run(map: &[&str]) -> Result<RunState, RunError> {
+        let map = map
+            .iter()
+            .map(|&enemy| Encounter {
+                enemy: enemy.to_string(),
+                boss: enemy == "Titan",
+            })
+            .collect();
+        RunState::with_map(9, CardDatabase::builtin(), enemies(), map)
+    }
+
+    /// Plays cards (ending the turn when none can be played) until the fight
+    /// is over.
+    fn fight(run: &mut RunState) {
+        while run.phase() == RunPhase::Combat {
+            let playable = (0..run.combat().get_hand().len())
+                .find(|&index| run.combat().can_afford(&run.combat().get_hand()[index]));
+            let action = playable.map_or(GameAction::EndTurn, GameAction::PlayCard);
+            run.apply(action).unwrap();
+        }
+    }
+
+    #[test]
+    fn won_fight_offers_a_reward_then_the_next_fight() {
+        let mut run = run(&["Pebble", "Boulder"]).unwrap();
+        fight(&mut run);
+
+        assert_eq!(run.phase(), RunPhase::Reward);
+        assert_eq!(run.reward_choices().len(), REWARD_CHOICES);
+        let deck_size = run.deck().len();
+        let picked = run.reward_choices()[1].clone();
+        run.choose_reward(Some(1)).unwrap();
+
+        assert_eq!(run.phase(), RunPhase::Combat);
+        assert_eq!(run.floor(), 1);
+        assert_eq!(run.deck().len(), deck_size + 1);
+        assert!(run.deck().contains(&picked));
+        assert!(run.reward_choices().is_empty());
+        assert_eq!(run.combat().player.health, run.health());
+
+        fight(&mut run);
+        assert_eq!(run.outcome(), Some(RunOutcome::Victory));
+    }
+
+    #[test]
+    fn skipping_a_reward_keeps_the_deck() {
+        let mut run = run(&["Pebble", "Boulder"]).unwrap();
+        fight(&mut run);
+        let deck = run.deck().to_vec();
+        run.choose_reward(None).unwrap();
+        assert_eq!(run.deck(), deck);
+        assert_eq!(run.phase(), RunPhase::Combat);
+    }
+
+    #[test]
+    fn invalid_reward_changes_nothing() {
+        let mut run = run(&["Pebble", "Pebble"]).unwrap();
+        fight(&mut run);
+        assert_eq!(
+            run.choose_reward(Some(REWARD_CHOICES)),
+            Err(RunError::InvalidReward {
+                index: REWARD_CHOICES,
+                choices: REWARD_CHOICES
+            })
+        );
+        assert_eq!(run.phase(), RunPhase::Reward);
+        assert_eq!(run.floor(), 0);
+        assert_eq!(run.reward_choices().len(), REWARD_CHOICES);
+    }
+
+    #[test]
+    fn dying_loses_the_run() {
+        let mut run = run(&["Titan", "Pebble"]).unwrap();
+        assert_eq!(
+            run.choose_reward(Some(0)),
+            Err(RunError::WrongPhase {
+                phase: RunPhase::Combat
+            })
+        );
+        fight(&mut run);
+        assert_eq!(run.outcome(), Some(RunOutcome::Defeat));
+        assert_eq!(run.health(), 0);
+        assert_eq!(
+            run.apply(GameAction::EndTurn).unwrap_err(),
+            RunError::WrongPhase {
+                phase: RunPhase::Over(RunOutcome::Defeat)
+            }
+        );
+    }
+
+    #[test]
+    fn map_with_an_unknown_enemy_is_rejected() {
+        assert_eq!(
+            run(&["Pebble", "Snowman"]).err(),
+            Some(RunError::UnknownEnemy("Snowman".to_string()))
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use crate::events::{Combatant, GameEvent};
+use crate::CoreGameState;
+use serde::{Deserialize, Serialize};
+
+/// A status effect either combatant can carry. Each effect has a number of
+/// stacks, and applying it again adds to them:
+///
+/// - `Block` absorbs attack damage. All of it expires at the start of its
+///   owner's next turn.
+/// - `Poison` deals its stacks as damage at the start of its owner's turn,
+///   ignoring block, then loses one stack.
+/// - `Vulnerable` makes attacks against its owner deal 50% more damage. Each
+///   attack it affects uses up one stack.
+/// - `Weak` makes its owner's attacks deal 25% less damage. Each attack it
+///   affects uses up one stack.
+/// - `Strength` adds its stacks to every attack its owner makes and never
+///   wears off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusEffect {
+    Block,
+    Poison,
+    Vulnerable,
+    Weak,
+    Strength,
+}
+
+impl StatusEffect {
+    pub const ALL: [StatusEffect; 5] = [
+        StatusEffect::Block,
+        StatusEffect::Poison,
+        StatusEffect::Vulnerable,
+        StatusEffect::Weak,
+        StatusEffect::Strength,
+    ];
+
+    /// A stable id for the effect, for frontends to match on. The display
+    /// name is `render::render_status`.
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusEffect::Block => "block",
+            StatusEffect::Poison => "poison",
+            StatusEffect::Vulnerable => "vulnerable",
+            StatusEffect::Weak => "weak",
+            StatusEffect::Strength => "strength",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<StatusEffect> {
+        StatusEffect::ALL
+            .into_iter()
+            .find(|effect| effect.name() == name)
+    }
+}
+
+/// The stacks of every status effect on one combatant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Statuses {
+    block: u32,
+    poison: u32,
+    vulnerable: u32,
+    weak: u32,
+    strength: u32,
+}
+
+impl Statuses {
+    pub fn get(&self, effect: StatusEffect) -> u32 {
+        match effect {
+            StatusEffect::Block => self.block,
+            StatusEffect::Poison => self.poison,
+            StatusEffect::Vulnerable => self.vulnerable,
+            StatusEffect::Weak => self.weak,
+            StatusEffect::Strength => self.strength,
+        }
+    }
+
+    fn stacks_mut(&mut self, effect: StatusEffect) -> &mut u32 {
+        match effect {
+            StatusEffect::Block => &mut self.block,
+            StatusEffect::Poison => &mut self.poison,
+            StatusEffect::Vulnerable => &mut self.vulnerable,
+            StatusEffect::Weak => &mut self.weak,
+            StatusEffect::Strength => &mut self.strength,
+        }
+    }
+
+    /// Adds `stacks` of `effect` and returns the new total.
+    pub fn apply(&mut self, effect: StatusEffect, stacks: u32) -> u32 {
+        let total = self.stacks_mut(effect);
+        *total = total.saturating_add(stacks);
+        *total
+    }
+
+    /// Effects with at least one stack, in `StatusEffect::ALL` order.
+    pub fn active(&self) -> impl Iterator<Item = (StatusEffect, u32)> + '_ {
+        StatusEffect::ALL
+            .into_iter()
+            .map(|effect| (effect, self.get(effect)))
+            .filter(|(_, stacks)| *stacks > 0)
+    }
+
+    /// Uses up block against `damage` and returns how much was absorbed.
+    pub fn absorb(&mut self, damage: u32) -> u32 {
+        let blocked = damage.min(self.block);
+        self.block -= blocked;
+        blocked
+    }
+
+    /// Expires block and ticks poison, returning the poison damage to deal.
+    pub fn start_of_turn(&mut self) -> u32 {
+        self.block = 0;
+        let poison = self.poison;
+        self.poison = self.poison.saturating_sub(1);
+        poison
+    }
+
+    /// Damage an attack of `base` would deal after Strength, Weak and
+    /// Vulnerable, before block.
+    pub fn attack_damage(base: u32, attacker: &Statuses, defender: &Statuses) -> u32 {
+        if base == 0 {
+            return 0;
+        }
+        let mut damage = base.saturating_add(attacker.strength);
+        if attacker.weak > 0 {
+            damage = damage * 3 / 4;
+        }
+        if defender.vulnerable > 0 {
+            damage = damage * 3 / 2;
+        }
+        damage
+    }
+
+    /// Like `attack_damage`, but uses up the Weak and Vulnerable stacks that
+    /// the attack triggers.
+    pub fn resolve_attack(base: u32, attacker: &mut Statuses, defender: &mut Statuses) -> u32 {
+        let damage = Self::attack_damage(base, attacker, defender);
+        if base > 0 {
+            attacker.weak = attacker.weak.saturating_sub(1);
+            defender.vulnerable = defender.vulnerable.saturating_sub(1);
+        }
+        damage
+    }
+}
+
+impl CoreGameState {
+    pub fn statuses(&self, target: Combatant) -> &Statuses {
+        match target {
+            Combatant::Player => &self.player.statuses,
+            Combatant::Enemy => &self.enemy.statuses,
+        }
+    }
+
+    fn statuses_mut(&mut self, target: Combatant) -> &mut Statuses {
+        match target {
+            Combatant::Player => &mut self.player.statuses,
+            Combatant::Enemy => &mut self.enemy.statuses,
+        }
+    }
+
+    pub(crate) fn apply_status(&mut self, target: Combatant, effect: StatusEffect, stacks: u32) {
+        let total = self.statuses_mut(target).apply(effect, stacks);
+        self.log(GameEvent::StatusApplied {
+            target,
+            effect,
+            stacks,
+            total,
+        });
+    }
+
+    /// Takes `damage` off the target's block and returns what gets through.
+    pub(crate) fn absorb_with_block(&mut self, target: Combatant, damage: u32) -> u32 {
+        let blocked = self.statuses_mut(target).absorb(damage);
+        if blocked > 0 {
+            self.log(GameEvent::DamageBlocked {
+                target,
+                amount: blocked,
+            });
+        }
+        damage - blocked
+    }
+
+    /// Start-of-turn upkeep for `target`'s statuses. Returns the poison
+    /// damage dealt, which has already been logged.
+    pub(crate) fn tick_statuses(&mut self, target: Combatant) -> u32 {
+        let poison = self.statuses_mut(target).start_of_turn();
+        if poison == 0 {
+            return 0;
+        }
+        let health = match target {
+            Combatant::Player => &mut self.player.health,
+            Combatant::Enemy => &mut self.enemy.health,
+        };
+        let health_before = *health;
+        *health = health.saturating_sub(poison);
+        let health_after = *health;
+        self.log(GameEvent::PoisonTicked {
+            target,
+            amount: health_before - health_after,
+            health_after,
+        });
+        poison
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applying_again_adds_stacks() {
+        let mut statuses = Statuses::default();
+        assert_eq!(statuses.apply(StatusEffect::Block, 3), 3);
+        assert_eq!(statuses.apply(StatusEffect::Block, 4), 7);
+        assert_eq!(statuses.apply(StatusEffect::Poison, 2), 2);
+        assert_eq!(statuses.get(StatusEffect::Block), 7);
+        assert_eq!(
+            statuses.active().collect::<Vec<_>>(),
+            [(StatusEffect::Block, 7), (StatusEffect::Poison, 2)]
+        );
+    }
+
+    #[test]
+    fn block_absorbs_damage_and_expires_at_turn_start() {
+        let mut statuses = Statuses::default();
+        statuses.apply(StatusEffect::Block, 5);
+        assert_eq!(statuses.absorb(3), 3);
+        assert_eq!(statuses.absorb(4), 2);
+        assert_eq!(statuses.get(StatusEffect::Block), 0);
+
+        statuses.apply(StatusEffect::Block, 5);
+        statuses.start_of_turn();
+        assert_eq!(statuses.get(StatusEffect::Block), 0);
+    }
+
+    #[test]
+    fn poison_ticks_down_one_stack_a_turn() {
+        let mut statuses = Statuses::default();
+        statuses.apply(StatusEffect::Poison, 3);
+        let ticks = (0..4).map(|_| statuses.start_of_turn()).collect::<Vec<_>>();
+        assert_eq!(ticks, [3, 2, 1, 0]);
+        assert_eq!(statuses.get(StatusEffect::Poison), 0);
+    }
+
+    #[test]
+    fn weak_and_vulnerable_wear_off_per_attack_and_strength_stays() {
+        let mut attacker = Statuses::default();
+        let mut defender = Statuses::default();
+        attacker.apply(StatusEffect::Strength, 2);
+        attacker.apply(StatusEffect::Weak, 1);
+        defender.apply(StatusEffect::Vulnerable, 2);
+
+        // (4 + 2) * 3/4 = 4, then * 3/2 = 6
+        assert_eq!(Statuses::resolve_attack(4, &mut attacker, &mut defender), 6);
+        // Weak is used up: (4 + 2) * 3/2 = 9
+        assert_eq!(Statuses::resolve_attack(4, &mut attacker, &mut defender), 9);
+        assert_eq!(Statuses::resolve_attack(4, &mut attacker, &mut defender), 6);
+        assert_eq!(attacker.get(StatusEffect::Strength), 2);
+        assert_eq!(defender.get(StatusEffect::Vulnerable), 0);
+    }
+
+    #[test]
+    fn enemy_shield_up_is_block() {
+        let mut game = CoreGameState::with_seed(7);
+        game.enemy_intent = Some(crate::intents::EnemyIntent::ShieldUp { amount: 4 });
+        game.act_on_intent();
+        assert_eq!(game.statuses(Combatant::Enemy).get(StatusEffect::Block), 4);
+    }
+}
@@ -0,0 +1,504 @@
+//! A small procedural synthesizer for sound effects: one oscillator per
+//! sound, shaped by an ADSR envelope, a pitch sweep and an optional filter.
+//! Nothing here depends on Godot; `SynthPlayer` feeds `Synth` to the audio
+//! server, and `render` and `write_wav` turn a sound into a WAV file.
+
+use std::f32::consts::PI;
+use std::io;
+use std::path::Path;
+
+pub const SAMPLE_RATE: f32 = 44100.0;
+
+/// Sounds playing at once past this many cut off the oldest.
+pub const MAX_VOICES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Saw,
+    /// White noise; the pitch is ignored.
+    Noise,
+}
+
+impl Waveform {
+    /// The waveform's value at `phase`, which runs from 0 to 1 over a
+    /// cycle. `noise` is the noise generator's state.
+    fn sample(self, phase: f32, noise: &mut u32) -> f32 {
+        match self {
+            Waveform::Sine => (phase * 2.0 * PI).sin(),
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Saw => 2.0 * phase - 1.0,
+            Waveform::Noise => {
+                // xorshift32, so sounds come out the same on every run
+                *noise ^= *noise << 13;
+                *noise ^= *noise >> 17;
+                *noise ^= *noise << 5;
+                *noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+            }
+        }
+    }
+}
+
+/// Attack, decay and release are in seconds; `sustain` is the level held
+/// between decay and release, from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Envelope {
+    /// The level `time` seconds into a sound held for `held` seconds.
+    pub fn level(&self, time: f32, held: f32) -> f32 {
+        if time < held {
+            self.held_level(time)
+        } else if time < held + self.release {
+            self.held_level(held) * (1.0 - (time - held) / self.release)
+        } else {
+            0.0
+        }
+    }
+
+    fn held_level(&self, time: f32) -> f32 {
+        if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (time - self.attack) / self.decay
+        } else {
+            self.sustain
+        }
+    }
+}
+
+/// A one-pole filter. Cutoffs are in Hz.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    None,
+    LowPass(f32),
+    HighPass(f32),
+}
+
+/// Everything needed to synthesize one sound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundPreset {
+    pub waveform: Waveform,
+    /// Pitch at the start of the sound, in Hz.
+    pub start_frequency: f32,
+    /// Pitch at the end of the sound. The pitch slides exponentially from
+    /// `start_frequency`, so a sweep sounds even across octaves.
+    pub end_frequency: f32,
+    /// How long the sound is held before its release, in seconds.
+    pub duration: f32,
+    pub envelope: Envelope,
+    pub filter: Filter,
+    /// Peak amplitude, from 0 to 1.
+    pub volume: f32,
+}
+
+impl SoundPreset {
+    /// The sound's full length including its release, in seconds.
+    pub fn length(&self) -> f32 {
+        self.duration + self.envelope.release
+    }
+
+    /// `MyPlayer`'s bounce: a sine blip fading out linearly over 100ms.
+    pub fn boop(frequency: f32) -> Self {
+        SoundPreset {
+            waveform: Waveform::Sine,
+            start_frequency: frequency,
+            end_frequency: frequency,
+            duration: 0.1,
+            envelope: Envelope {
+                attack: 0.0,
+                decay: 0.1,
+                sustain: 0.0,
+                release: 0.0,
+            },
+            filter: Filter::None,
+            volume: 1.0,
+        }
+    }
+}
+
+/// The game's named sound effects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    CardPlay,
+    Hit,
+    Heal,
+    Poison,
+    Stun,
+}
+
+impl Sound {
+    pub const ALL: [Sound; 5] = [
+        Sound::CardPlay,
+        Sound::Hit,
+        Sound::Heal,
+        Sound::Poison,
+        Sound::Stun,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sound::CardPlay => "card_play",
+            Sound::Hit => "hit",
+            Sound::Heal => "heal",
+            Sound::Poison => "poison",
+            Sound::Stun => "stun",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Sound> {
+        Sound::ALL.into_iter().find(|sound| sound.name() == name)
+    }
+
+    pub fn preset(self) -> SoundPreset {
+        match self {
+            // A quick rising chirp
+            Sound::CardPlay => SoundPreset {
+                waveform: Waveform::Square,
+                start_frequency: 440.0,
+                end_frequency: 880.0,
+                duration: 0.06,
+                envelope: Envelope {
+                    attack: 0.005,
+                    decay: 0.04,
+                    sustain: 0.4,
+                    release: 0.05,
+                },
+                filter: Filter::LowPass(3000.0),
+                volume: 0.25,
+            },
+            // A short muffled thud
+            Sound::Hit => SoundPreset {
+                waveform: Waveform::Noise,
+                start_frequency: 0.0,
+                end_frequency: 0.0,
+                duration: 0.1,
+                envelope: Envelope {
+                    attack: 0.002,
+                    decay: 0.09,
+                    sustain: 0.0,
+                    release: 0.05,
+                },
+                filter: Filter::LowPass(1200.0),
+                volume: 0.6,
+            },
+            // A soft sine rising an octave
+            Sound::Heal => SoundPreset {
+                waveform: Waveform::Sine,
+                start_frequency: 523.25,
+                end_frequency: 1046.5,
+                duration: 0.25,
+                envelope: Envelope {
+                    attack: 0.05,
+                    decay: 0.1,
+                    sustain: 0.6,
+                    release: 0.2,
+                },
+                filter: Filter::None,
+                volume: 0.4,
+            },
+            // A dark buzz sinking an octave
+            Sound::Poison => SoundPreset {
+                waveform: Waveform::Saw,
+                start_frequency: 220.0,
+                end_frequency: 110.0,
+                duration: 0.3,
+                envelope: Envelope {
+                    attack: 0.02,
+                    decay: 0.1,
+                    sustain: 0.5,
+                    release: 0.15,
+                },
+                filter: Filter::LowPass(800.0),
+                volume: 0.3,
+            },
+            // A thin falling zap
+            Sound::Stun => SoundPreset {
+                waveform: Waveform::Square,
+                start_frequency: 1760.0,
+                end_frequency: 440.0,
+                duration: 0.2,
+                envelope: Envelope {
+                    attack: 0.005,
+                    decay: 0.05,
+                    sustain: 0.5,
+                    release: 0.1,
+                },
+                filter: Filter::HighPass(400.0),
+                volume: 0.2,
+            },
+        }
+    }
+}
+
+/// One sound being played.
+struct Voice {
+    preset: SoundPreset,
+    /// Samples generated so far.
+    position: u32,
+    phase: f32,
+    noise: u32,
+    /// The one-pole filter's running low-pass output.
+    filtered: f32,
+}
+
+impl Voice {
+    fn new(preset: SoundPreset, noise_seed: u32) -> Self {
+        Voice {
+            preset,
+            position: 0,
+            phase: 0.0,
+            // xorshift never leaves 0
+            noise: noise_seed.max(1),
+            filtered: 0.0,
+        }
+    }
+
+    /// The next sample, or `None` once the sound is over.
+    fn next_sample(&mut self, sample_rate: f32) -> Option<f32> {
+        let preset = &self.preset;
+        let time = self.position as f32 / sample_rate;
+        if time >= preset.length() {
+            return None;
+        }
+        self.position += 1;
+
+        let progress = time / preset.length();
+        let frequency = if preset.start_frequency > 0.0 && preset.end_frequency > 0.0 {
+            preset.start_frequency * (preset.end_frequency / preset.start_frequency).powf(progress)
+        } else {
+            preset.start_frequency
+        };
+        let raw = preset.waveform.sample(self.phase, &mut self.noise);
+        self.phase = (self.phase + frequency / sample_rate).fract();
+
+        let sample = match preset.filter {
+            Filter::None => raw,
+            Filter::LowPass(cutoff) => {
+                self.filtered += filter_coefficient(cutoff, sample_rate) * (raw - self.filtered);
+                self.filtered
+            }
+            Filter::HighPass(cutoff) => {
+                self.filtered += filter_coefficient(cutoff, sample_rate) * (raw - self.filtered);
+                raw - self.filtered
+            }
+        };
+        Some(sample * preset.envelope.level(time, preset.duration) * preset.volume)
+    }
+}
+
+fn filter_coefficient(cutoff: f32, sample_rate: f32) -> f32 {
+    1.0 - (-2.0 * PI * cutoff / sample_rate).exp()
+}
+
+/// Mixes any number of sounds into one mono stream.
+pub struct Synth {
+    sample_rate: f32,
+    voices: Vec<Voice>,
+    /// Seeds each new voice's noise, so overlapping noises don't cancel out.
+    sounds_started: u32,
+}
+
+impl Synth {
+    pub fn new(sample_rate: f32) -> Self {
+        Synth {
+            sample_rate,
+            voices: Vec::new(),
+            sounds_started: 0,
+        }
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Starts a sound on top of whatever is already playing.
+    pub fn play(&mut self, preset: SoundPreset) {
+        if self.voices.len() >= MAX_VOICES {
+            self.voices.remove(0);
+        }
+        self.sounds_started = self.sounds_started.wrapping_add(1);
+        let seed = self.sounds_started.wrapping_mul(0x9e37_79b9);
+        self.voices.push(Voice::new(preset, seed));
+    }
+
+    /// Whether no sound is playing.
+    pub fn is_silent(&self) -> bool {
+        self.voices.is_empty()
+    }
+
+    /// Writes the next `out.len()` samples of the mix, clipped to -1..=1.
+    /// Sounds that have ended are dropped.
+    pub fn fill(&mut self, out: &mut [f32]) {
+        out.fill(0.0);
+        let sample_rate = self.sample_rate;
+        self.voices.retain_mut(|voice| {
+            for sample in out.iter_mut() {
+                match voice.next_sample(sample_rate) {
+                    Some(value) => *sample += value,
+                    None => return false,
+                }
+            }
+            true
+        });
+        for sample in out.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// Renders one sound from start to end, including its release.
+pub fn render(preset: SoundPreset, sample_rate: f32) -> Vec<f32> {
+    let mut synth = Synth::new(sample_rate);
+    synth.play(preset);
+    let mut samples = vec![0.0; (preset.length() * sample_rate).ceil() as usize];
+    synth.fill(&mut samples);
+    samples
+}
+
+/// Encodes mono samples in -1..=1 as a 16-bit PCM WAV file.
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    const CHANNELS: u16 = 1;
+    const BITS_PER_SAMPLE: u16 = 16;
+    let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+    let data_size = samples.len() as u32 * block_align as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // 1 is uncompressed PCM
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&CHANNELS.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        wav.extend_from_slice(&value.to_le_bytes());
+    }
+    wav
+}
+
+/// Writes mono samples to `path` as a 16-bit WAV file.
+pub fn write_wav(path: &Path, samples: &[f32], sample_rate: u32) -> io::Result<()> {
+    std::fs::write(path, encode_wav(samples, sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(waveform: Waveform, frequency: f32, envelope: Envelope) -> SoundPreset {
+        SoundPreset {
+            waveform,
+            start_frequency: frequency,
+            end_frequency: frequency,
+            duration: 0.5,
+            envelope,
+            filter: Filter::None,
+            volume: 1.0,
+        }
+    }
+
+    const FLAT: Envelope = Envelope {
+        attack: 0.0,
+        decay: 0.0,
+        sustain: 1.0,
+        release: 0.0,
+    };
+
+    #[test]
+    fn renders_duration_plus_release() {
+        let preset = SoundPreset {
+            duration: 0.25,
+            envelope: Envelope {
+                release: 0.5,
+                ..FLAT
+            },
+            ..tone(Waveform::Saw, 220.0, FLAT)
+        };
+        assert_eq!(render(preset, 8000.0).len(), 6000);
+    }
+
+    #[test]
+    fn sine_crosses_zero_twice_a_cycle() {
+        let samples = render(tone(Waveform::Sine, 440.0, FLAT), SAMPLE_RATE);
+        let crossings = samples
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count();
+        // Half a second of 440 Hz
+        assert!((439..=441).contains(&crossings), "{} crossings", crossings);
+    }
+
+    #[test]
+    fn envelope_peaks_after_attack_then_goes_silent() {
+        let envelope = Envelope {
+            attack: 0.1,
+            decay: 0.1,
+            sustain: 0.5,
+            release: 0.1,
+        };
+        let preset = SoundPreset {
+            volume: 0.8,
+            ..tone(Waveform::Square, 100.0, envelope)
+        };
+        let rate = 1000.0;
+        let peak = |from: f32, to: f32| {
+            render(preset, rate)[(from * rate) as usize..(to * rate) as usize]
+                .iter()
+                .fold(0.0f32, |peak, sample| peak.max(sample.abs()))
+        };
+        assert!((peak(0.09, 0.11) - 0.8).abs() < 0.02);
+        assert!((peak(0.3, 0.4) - 0.4).abs() < 1e-6);
+
+        let mut synth = Synth::new(rate);
+        synth.play(preset);
+        let mut out = vec![1.0; 1000];
+        synth.fill(&mut out);
+        assert!(out[600..].iter().all(|&sample| sample == 0.0));
+        assert!(synth.is_silent());
+    }
+
+    #[test]
+    fn stacked_voices_are_clamped() {
+        let mut synth = Synth::new(8000.0);
+        for _ in 0..4 {
+            synth.play(tone(Waveform::Square, 100.0, FLAT));
+        }
+        let mut out = vec![0.0; 800];
+        synth.fill(&mut out);
+        assert!(out.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+        assert!(out.contains(&1.0) && out.contains(&-1.0));
+    }
+
+    #[test]
+    fn wav_header_sizes() {
+        let wav = encode_wav(&[0.5; 100], 8000);
+        let u32_at = |at: usize| u32::from_le_bytes(wav[at..at + 4].try_into().unwrap());
+        assert_eq!(wav.len(), 44 + 200);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(4), 36 + 200);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(40), 200);
+        assert_eq!(u32_at(24), 8000);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Card, CoreGameState};
+use crate::{actions::GameAction, status::StatusEffect, Card, CoreGameState};
 
 pub struct TutorialState {
     pub step: usize,
@@ -7,7 +7,6 @@ pub struct TutorialState {
     player_health_before: u32,
     card_played: Option<Card>,
     enemy_damage_dealt: u32,
-    player_damage_taken: u32,
     enemy_turn_damage: u32,
 }
 
@@ -27,7 +26,6 @@ impl TutorialState {
             player_health_before,
             card_played: None,
             enemy_damage_dealt: 0,
-            player_damage_taken: 0,
             enemy_turn_damage: 0,
         }
     }
@@ -39,7 +37,7 @@ impl TutorialState {
     pub fn get_current_instruction(&self) -> String {
         match self.step {
             0 => "Welcome to the Deckbuilder Tutorial! Let's start by looking at your hand. Press Enter to continue.".to_string(),
-            1 => "You start with 5 cards in your hand. Each card has an Attack and Defense value. Press Enter to continue.".to_string(),
+            1 => "You start with 5 cards in your hand. Each card has an Attack and Defense value and a Mana Cost. You can play as many cards each turn as your mana allows. Press Enter to continue.".to_string(),
             2 => "Let's play your first card. Type '1' to play the first card in your hand.".to_string(),
             3 => {
                 let card = self.card_played.as_ref().unwrap();
@@ -53,30 +51,25 @@ BATTLE ACTION SUMMARY:
 2. Your Attack:
    Enemy Health: {} -> {}  (*-{} damage*)
 
-3. Enemy Counterattack:
-   Your Health: {} -> {}   (*-{} damage*)
-   {}
+3. Your Defense:
+   Block: {}   (*absorbs damage from the enemy's next attack*)
 
 4. Final Status:
-   YOU  - Health: {}, Cards in hand: {}
+   YOU  - Health: {}, Mana: {}/{}, Cards in hand: {}
    ENEMY - Health: {}, Attack: {}
 --------------------------------------------------
-Great job! You've successfully attacked the enemy and survived their counterattack.
+Great job! You've attacked the enemy and braced for its attack.
+You could keep playing cards while you have mana left.
 Press Enter to continue the tutorial."#,
                     card.name, card.attack, card.defense,
                     self.enemy_health_before, self.core_game.enemy.health, self.enemy_damage_dealt,
-                    self.player_health_before, self.core_game.player.health, self.player_damage_taken,
-                    if self.player_damage_taken < self.core_game.enemy.attack {
-                        format!("(You blocked {} damage with your {}'s defense!)",
-                            self.core_game.enemy.attack - self.player_damage_taken, card.name)
-                    } else {
-                        String::new()
-                    },
-                    self.core_game.player.health, self.core_game.player.hand.len(),
+                    self.core_game.player.statuses.get(StatusEffect::Block),
+                    self.core_game.player.health, self.core_game.player.current_mana,
+                    self.core_game.player.max_mana, self.core_game.player.hand.len(),
                     self.core_game.enemy.health, self.core_game.enemy.attack
                 )
             },
-            4 => "Now it's the enemy's turn. They will attack you. Press Enter to see what happens.".to_string(),
+            4 => "When you're done playing cards, you end your turn ('e' in the real game) and the enemy acts. The game always shows what the enemy intends to do next, so you can prepare for it. This time it intends to attack. Press Enter to end your turn.".to_string(),
             5 => format!(
                 "The enemy attacked you! You took {} damage. Your health decreased from {} to {}. The game continues until either you or the enemy runs out of health. Press Enter to continue.",
                 self.enemy_turn_damage,
@@ -94,13 +87,13 @@ Press Enter to continue the tutorial."#,
                     self.enemy_health_before = self.core_game.enemy.health;
                     self.player_health_before = self.core_game.player.health;
                     self.card_played = self.core_game.player.hand.get(0).cloned();
-                    let result = self.core_game.play_card(0);
-                    println!("{}", result); // Print the result of playing the card
+                    if let Err(err) = self.core_game.apply(GameAction::PlayCard(0)) {
+                        println!("{}", err); // Print why the card couldn't be played
+                    }
                     if let Some(_card) = &self.card_played {
-                        self.enemy_damage_dealt =
-                            self.enemy_health_before - self.core_game.enemy.health;
-                        self.player_damage_taken =
-                            self.player_health_before - self.core_game.player.health;
+                        self.enemy_damage_dealt = self
+                            .enemy_health_before
+                            .saturating_sub(self.core_game.enemy.health);
                     }
                     self.next_step();
                     String::new() // Return empty string when advancing
@@ -113,9 +106,12 @@ Press Enter to continue the tutorial."#,
                 if input.is_empty() {
                     if self.step == 4 {
                         self.player_health_before = self.core_game.player.health;
-                        self.core_game.enemy_turn();
-                        self.enemy_turn_damage =
-                            self.player_health_before - self.core_game.player.health;
+                        if let Err(err) = self.core_game.apply(GameAction::EndTurn) {
+                            println!("{}", err);
+                        }
+                        self.enemy_turn_damage = self
+                            .player_health_before
+                            .saturating_sub(self.core_game.player.health);
                         self.next_step();
                         format!(
                             "The enemy attacks! You take {} damage.",
@@ -0,0 +1,170 @@
+use crate::actions::ActionError;
+use crate::events::GameEvent;
+use crate::phase::TurnPhase;
+use crate::CoreGameState;
+
+/// Snapshots of the game before each undoable action this turn, and of the
+/// game before each undo, for redo.
+#[derive(Clone, Default)]
+pub(crate) struct History {
+    undo: Vec<CoreGameState>,
+    redo: Vec<CoreGameState>,
+    /// Set for games nobody undoes in, like a policy's rollouts, so `apply`
+    /// doesn't copy the whole game on every action.
+    disabled: bool,
+}
+
+impl History {
+    pub(crate) fn disabled() -> Self {
+        History {
+            disabled: true,
+            ..History::default()
+        }
+    }
+}
+
+impl CoreGameState {
+    pub fn can_undo(&self) -> bool {
+        !self.history.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.history.redo.is_empty()
+    }
+
+    /// Turns undo off (or back on) for this game, dropping its history. Games
+    /// played by bots run faster without it.
+    pub fn set_undo_enabled(&mut self, enabled: bool) {
+        self.history = if enabled {
+            History::default()
+        } else {
+            History::disabled()
+        };
+    }
+
+    /// Puts the game back as it was before the last action: hand, mana,
+    /// health, statuses, RNG and log. Only actions since the start of the
+    /// turn can be undone, and never past a card draw, since the player has
+    /// already seen the drawn card.
+    pub fn undo(&mut self) -> Result<(), ActionError> {
+        let previous = self.history.undo.pop().ok_or(ActionError::NothingToUndo)?;
+        let current = std::mem::replace(self, previous);
+        self.history = current.history;
+        self.history.redo.push(CoreGameState {
+            history: History::default(),
+            ..current
+        });
+        Ok(())
+    }
+
+    /// Reapplies the last undone action.
+    pub fn redo(&mut self) -> Result<(), ActionError> {
+        let next = self.history.redo.pop().ok_or(ActionError::NothingToRedo)?;
+        let current = std::mem::replace(self, next);
+        self.history = current.history;
+        self.history.undo.push(CoreGameState {
+            history: History::default(),
+            ..current
+        });
+        Ok(())
+    }
+
+    /// A copy of the game without its history, or nothing if undo is off.
+    pub(crate) fn snapshot(&mut self) -> Option<CoreGameState> {
+        if self.history.disabled {
+            return None;
+        }
+        let history = std::mem::take(&mut self.history);
+        let snapshot = self.clone();
+        self.history = history;
+        Some(snapshot)
+    }
+
+    /// Records an applied action. Actions that stay within the player's turn
+    /// and reveal nothing new can be undone; ending the turn, ending the game
+    /// or drawing a card clears the history instead.
+    pub(crate) fn update_history(&mut self, before: Option<CoreGameState>, events: &[GameEvent]) {
+        let Some(before) = before else {
+            return;
+        };
+        self.history.redo.clear();
+        let revealed = events
+            .iter()
+            .any(|event| matches!(event, GameEvent::CardDrawn { .. }));
+        if self.phase == TurnPhase::PlayerMain
+            && before.phase == TurnPhase::PlayerMain
+            && before.turn_counter == self.turn_counter
+            && !revealed
+        {
+            self.history.undo.push(before);
+        } else {
+            self.history.undo.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::GameAction;
+    use crate::{Card, Player, SpecialAbility};
+
+    fn game_with(card: Card) -> CoreGameState {
+        CoreGameState::with_player(3, Player::with_deck(vec![card; 10]))
+    }
+
+    fn strike() -> Card {
+        Card {
+            name: "Strike".to_string(),
+            attack: 1,
+            defense: 0,
+            lucky: false,
+            special_ability: None,
+            mana_cost: 1,
+        }
+    }
+
+    #[test]
+    fn undo_then_redo_restores_each_state() {
+        let mut game = game_with(strike());
+        game.apply(GameAction::PlayCard(0)).unwrap();
+        let after_one = game.state_hash();
+        game.apply(GameAction::PlayCard(0)).unwrap();
+        let after_two = game.state_hash();
+
+        game.undo().unwrap();
+        assert_eq!(game.state_hash(), after_one);
+        assert_eq!(game.get_hand().len(), 4);
+        game.redo().unwrap();
+        assert_eq!(game.state_hash(), after_two);
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn cannot_undo_a_card_draw() {
+        let mut game = game_with(Card {
+            name: "Scout".to_string(),
+            special_ability: Some(SpecialAbility::DrawCards(1)),
+            ..strike()
+        });
+        game.apply(GameAction::PlayCard(0)).unwrap();
+        assert_eq!(game.undo(), Err(ActionError::NothingToUndo));
+    }
+
+    #[test]
+    fn cannot_undo_into_the_previous_turn() {
+        let mut game = game_with(strike());
+        game.apply(GameAction::PlayCard(0)).unwrap();
+        game.apply(GameAction::EndTurn).unwrap();
+        assert_eq!(game.undo(), Err(ActionError::NothingToUndo));
+    }
+
+    #[test]
+    fn no_snapshots_with_undo_off() {
+        let mut game = game_with(strike());
+        game.set_undo_enabled(false);
+        game.apply(GameAction::PlayCard(0)).unwrap();
+        assert!(!game.can_undo());
+        assert_eq!(game.undo(), Err(ActionError::NothingToUndo));
+    }
+}